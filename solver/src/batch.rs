@@ -0,0 +1,514 @@
+use std::collections::HashMap;
+
+use fraction::{GenericFraction, Zero};
+use num_bigint::BigUint;
+use num_rational::Ratio;
+use serde_json::{Map, Value};
+use solver::{SolverError, SolverResult};
+
+use crate::{
+    parse_number, parse_probability, scientific, Method,
+    Options,
+};
+
+const USAGE: &str = "\
+Usage: solver batch <file> [--format csv|jsonl] [--precision <D>]
+
+Reads one scenario per CSV row or JSONL line, picked by the file extension,
+and writes one row per scenario to stdout with the exact Bernoulli value,
+every approximation, their errors and timings. Edgeworth and Stirling only
+take a single k and hold the error message for intervals.
+
+CSV files need a header row. Blank lines and lines starting with # are
+skipped, quoted fields aren't supported. JSONL lines are objects with the
+same keys:
+    n, k, p                 Required, p as a fraction or a decimal
+    to                      Computes P(k <= X <= to) instead of P(X = k)
+    iterations, until_stable, reduce, sqrt_iterations, sqrt_digits,
+    precision, order, terms Same as the options of the single methods
+
+Options:
+    --format <F>            csv or jsonl [default: csv]
+    --precision <D>         Default precision of the scenarios [default: 50]";
+
+#[derive(Clone, Copy)]
+enum Output {
+    Csv,
+    Jsonl,
+}
+
+// Outcome of one method for a scenario. The errors are
+// measured against the exact Bernoulli value
+struct Evaluation {
+    // Decimal with the precision of the scenario, or the error
+    value: String,
+    absolute_error: Option<String>,
+    relative_error: Option<String>,
+    took_ns: Option<u64>,
+}
+
+struct Record {
+    line: usize,
+    n: u32,
+    k: u32,
+    to: Option<u32>,
+    p: String,
+    exact: Evaluation,
+    moivre_laplace: Evaluation,
+    continuity_corrected: Evaluation,
+    edgeworth: Evaluation,
+    stirling: Evaluation,
+    saddle_point: Evaluation,
+    poisson: Evaluation,
+}
+
+const CSV_HEADER: &str = "line,n,k,to,p,\
+exact,exact_ns,\
+moivre_laplace,moivre_laplace_absolute_error,moivre_laplace_relative_error,moivre_laplace_ns,\
+continuity_corrected,continuity_corrected_absolute_error,continuity_corrected_relative_error,continuity_corrected_ns,\
+edgeworth,edgeworth_absolute_error,edgeworth_relative_error,edgeworth_ns,\
+stirling,stirling_absolute_error,stirling_relative_error,stirling_ns,\
+saddle_point,saddle_point_absolute_error,saddle_point_relative_error,saddle_point_ns,\
+poisson,poisson_absolute_error,poisson_relative_error,poisson_ns";
+
+pub fn run(
+    mut args: impl Iterator<Item = String>,
+) -> Result<(), String> {
+    let mut path = None;
+    let mut output = Output::Csv;
+    let mut precision = 50;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            "--format" | "--precision" => {
+                let value =
+                    args.next().ok_or_else(|| {
+                        format!("{} expects a value", arg)
+                    })?;
+                if arg == "--format" {
+                    output = match value.as_str() {
+                        "csv" => Output::Csv,
+                        "jsonl" => Output::Jsonl,
+                        _ => {
+                            return Err(format!(
+                                "Unknown format {}",
+                                value
+                            ))
+                        }
+                    };
+                } else {
+                    precision = parse_number(&arg, &value)?;
+                }
+            }
+            _ if path.is_none()
+                && !arg.starts_with('-') =>
+            {
+                path = Some(arg)
+            }
+            _ => {
+                return Err(format!(
+                    "Unexpected argument {}\n\n{}",
+                    arg, USAGE
+                ))
+            }
+        }
+    }
+
+    let path = path.ok_or_else(|| {
+        format!("Missing file\n\n{}", USAGE)
+    })?;
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|error| format!("{}: {}", path, error))?;
+    let scenarios = if path.ends_with(".jsonl")
+        || path.ends_with(".json")
+    {
+        read_jsonl(&contents)?
+    } else {
+        read_csv(&contents)?
+    };
+
+    if let Output::Csv = output {
+        println!("{}", CSV_HEADER);
+    }
+
+    for (line, fields) in scenarios {
+        let (mut options, p) = scenario(&fields, precision)
+            .map_err(|error| {
+                format!("{}:{}: {}", path, line, error)
+            })?;
+        let record = evaluate(line, &mut options, p);
+
+        match output {
+            Output::Csv => println!("{}", csv_row(&record)),
+            Output::Jsonl => {
+                println!("{}", jsonl_row(&record))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Line number along with the fields of every scenario
+type Scenarios = Vec<(usize, HashMap<String, String>)>;
+
+fn read_csv(contents: &str) -> Result<Scenarios, String> {
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| {
+            !line.is_empty() && !line.starts_with('#')
+        });
+
+    let Some((_, header)) = lines.next() else {
+        return Ok(Vec::new());
+    };
+    let header: Vec<&str> =
+        header.split(',').map(str::trim).collect();
+
+    lines
+        .map(|(line, row)| {
+            let values: Vec<&str> =
+                row.split(',').map(str::trim).collect();
+            if values.len() != header.len() {
+                return Err(format!(
+                    "Line {} has {} fields, the header has {}",
+                    line,
+                    values.len(),
+                    header.len()
+                ));
+            }
+
+            // Empty cells are left out, so that they fall back
+            // to the defaults
+            let fields = header
+                .iter()
+                .zip(values)
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(key, value)| {
+                    (key.to_string(), value.to_string())
+                })
+                .collect();
+            Ok((line, fields))
+        })
+        .collect()
+}
+
+fn read_jsonl(contents: &str) -> Result<Scenarios, String> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let object: serde_json::Map<
+                String,
+                serde_json::Value,
+            > = serde_json::from_str(line).map_err(
+                |error| {
+                    format!("Line {}: {}", index + 1, error)
+                },
+            )?;
+
+            // Numbers are kept as text so that both formats go
+            // through the same parsing, p stays exact that way
+            let fields = object
+                .into_iter()
+                .filter_map(|(key, value)| match value {
+                    serde_json::Value::Null => None,
+                    serde_json::Value::String(value) => {
+                        Some((key, value))
+                    }
+                    value => Some((key, value.to_string())),
+                })
+                .collect();
+            Ok((index + 1, fields))
+        })
+        .collect()
+}
+
+fn scenario(
+    fields: &HashMap<String, String>,
+    precision: usize,
+) -> Result<(Options, String), String> {
+    let mut options = Options {
+        precision,
+        ..Options::default()
+    };
+    let mut p = None;
+
+    for (key, value) in fields {
+        match key.as_str() {
+            "n" => {
+                options.experiments =
+                    parse_number(key, value)?
+            }
+            "k" => {
+                options.outcomes = parse_number(key, value)?
+            }
+            "to" => {
+                options.to = Some(parse_number(key, value)?)
+            }
+            "p" => {
+                options.probability =
+                    parse_probability(value)?;
+                p = Some(value.clone());
+            }
+            "iterations" => {
+                options.iterations =
+                    parse_number(key, value)?
+            }
+            "until_stable" => {
+                options.until_stable =
+                    Some(parse_number(key, value)?)
+            }
+            "reduce" => {
+                options.reduce = match value.as_str() {
+                    "true" | "1" => true,
+                    "false" | "0" => false,
+                    _ => {
+                        return Err(format!(
+                    "reduce expects true or false, got {}",
+                    value
+                ))
+                    }
+                }
+            }
+            "sqrt_iterations" => {
+                options.sqrt_iterations =
+                    parse_number(key, value)?
+            }
+            "sqrt_digits" => {
+                options.sqrt_digits =
+                    Some(parse_number(key, value)?)
+            }
+            "precision" => {
+                options.precision =
+                    parse_number(key, value)?
+            }
+            "order" => {
+                options.order = parse_number(key, value)?
+            }
+            "terms" => {
+                options.terms = parse_number(key, value)?
+            }
+            _ => {
+                return Err(format!(
+                    "Unknown field {}",
+                    key
+                ))
+            }
+        }
+    }
+
+    for required in ["n", "k"] {
+        if !fields.contains_key(required) {
+            return Err(format!("Missing {}", required));
+        }
+    }
+    let p = p.ok_or("Missing p")?;
+
+    Ok((options, p))
+}
+
+fn evaluate(
+    line: usize,
+    options: &mut Options,
+    p: String,
+) -> Record {
+    let mut solve = |method| {
+        options.method = method;
+        options.solve()
+    };
+    let exact = solve(Method::Bernoulli);
+    let moivre_laplace = solve(Method::MoivreLaplace);
+    let continuity_corrected =
+        solve(Method::ContinuityCorrected);
+    let edgeworth = solve(Method::Edgeworth);
+    let stirling = solve(Method::Stirling);
+    let saddle_point = solve(Method::SaddlePoint);
+    let poisson = solve(Method::Poisson);
+
+    let exact_value = exact
+        .as_ref()
+        .ok()
+        .map(|result| ratio(&result.probability));
+    let evaluation = |result| {
+        evaluation(result, exact_value.as_ref(), options)
+    };
+
+    Record {
+        line,
+        n: options.experiments,
+        k: options.outcomes,
+        to: options.to,
+        p,
+        moivre_laplace: evaluation(moivre_laplace),
+        continuity_corrected: evaluation(
+            continuity_corrected,
+        ),
+        edgeworth: evaluation(edgeworth),
+        stirling: evaluation(stirling),
+        saddle_point: evaluation(saddle_point),
+        poisson: evaluation(poisson),
+        exact: evaluation(exact),
+    }
+}
+
+fn evaluation(
+    result: Result<SolverResult, SolverError>,
+    exact: Option<&Ratio<BigUint>>,
+    options: &Options,
+) -> Evaluation {
+    let precision = options.precision;
+    let solved = match &result {
+        Ok(solved) => solved,
+        Err(error) => {
+            return Evaluation {
+                value: error.to_string(),
+                absolute_error: None,
+                relative_error: None,
+                took_ns: None,
+            }
+        }
+    };
+
+    let value = ratio(&solved.probability);
+    let absolute = exact.map(|exact| {
+        if &value >= exact {
+            &value - exact
+        } else {
+            exact - &value
+        }
+    });
+    let relative = absolute.as_ref().zip(exact).and_then(
+        |(absolute, exact)| {
+            (!exact.numer().is_zero())
+                .then(|| absolute / exact)
+        },
+    );
+    let display = |ratio: Ratio<BigUint>| {
+        scientific(
+            &GenericFraction::Rational(
+                fraction::Sign::Plus,
+                ratio,
+            ),
+            precision,
+        )
+    };
+
+    Evaluation {
+        value: format!(
+            "{:.precision$}",
+            solved.probability
+        ),
+        absolute_error: absolute.map(display),
+        relative_error: relative.map(display),
+        took_ns: Some(
+            solved.took.whole_nanoseconds() as u64
+        ),
+    }
+}
+
+fn ratio(
+    fraction: &GenericFraction<BigUint>,
+) -> Ratio<BigUint> {
+    match fraction {
+        GenericFraction::Rational(_, ratio) => {
+            ratio.clone()
+        }
+        _ => Ratio::new_raw(0u32.into(), 1u32.into()),
+    }
+}
+
+fn csv_row(record: &Record) -> String {
+    fields(record)
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// Same columns as the CSV, so both formats hold the decimals
+// rather than the fractions behind them. Counts and timings
+// become numbers and empty fields null
+fn jsonl_row(record: &Record) -> String {
+    let row: Map<String, Value> = CSV_HEADER
+        .split(',')
+        .zip(fields(record))
+        .map(|(column, field)| {
+            let integer =
+                matches!(column, "line" | "n" | "k" | "to")
+                    || column.ends_with("_ns");
+            let value = match field.parse::<u64>() {
+                _ if field.is_empty() => Value::Null,
+                Ok(number) if integer => number.into(),
+                _ => field.into(),
+            };
+            (column.to_string(), value)
+        })
+        .collect();
+    Value::Object(row).to_string()
+}
+
+// Every column of a row in the order of CSV_HEADER
+fn fields(record: &Record) -> Vec<String> {
+    let optional = |value: &Option<String>| {
+        value.clone().unwrap_or_default()
+    };
+    let took = |evaluation: &Evaluation| {
+        evaluation
+            .took_ns
+            .map(|took| took.to_string())
+            .unwrap_or_default()
+    };
+
+    let approximation = |evaluation: &Evaluation| {
+        [
+            evaluation.value.clone(),
+            optional(&evaluation.absolute_error),
+            optional(&evaluation.relative_error),
+            took(evaluation),
+        ]
+    };
+
+    [
+        record.line.to_string(),
+        record.n.to_string(),
+        record.k.to_string(),
+        record
+            .to
+            .map(|to| to.to_string())
+            .unwrap_or_default(),
+        record.p.clone(),
+        record.exact.value.clone(),
+        took(&record.exact),
+    ]
+    .into_iter()
+    .chain(
+        [
+            &record.moivre_laplace,
+            &record.continuity_corrected,
+            &record.edgeworth,
+            &record.stirling,
+            &record.saddle_point,
+            &record.poisson,
+        ]
+        .into_iter()
+        .flat_map(approximation),
+    )
+    .collect()
+}
+
+// Error messages can contain commas and quotes
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,145 @@
+use num_bigint::BigUint;
+
+use crate::{Cancellation, SolverError};
+
+// C(n, k) from its prime factorisation. By Legendre's formula the exponent of a prime p in
+// n! / (k! (n-k)!) is sum(floor(n/p^i) - floor(k/p^i) - floor((n-k)/p^i)), which by Kummer's
+// theorem is the amount of carries when adding k and n-k in base p. The prime powers are then
+// multiplied together with a product tree, so the big multiplications happen between numbers of
+// similar size
+pub fn binomial(
+    n: u32,
+    k: u32,
+    cancellation: &Cancellation,
+) -> Result<BigUint, SolverError> {
+    if k > n {
+        return Ok(BigUint::from(0u32));
+    }
+
+    let k = k.min(n - k);
+    let rest = n - k;
+
+    let factors = primes(n)
+        .into_iter()
+        .filter_map(|prime| {
+            let exponent =
+                legendre_exponent(n, k, rest, prime);
+            (exponent > 0)
+                .then(|| BigUint::from(prime).pow(exponent))
+        })
+        .collect();
+
+    product_tree(factors, cancellation)
+}
+
+fn legendre_exponent(
+    n: u32,
+    k: u32,
+    rest: u32,
+    prime: u32,
+) -> u32 {
+    let prime = prime as u64;
+    let mut power = prime;
+    let mut exponent = 0;
+    while power <= n as u64 {
+        exponent += n as u64 / power
+            - k as u64 / power
+            - rest as u64 / power;
+        power *= prime;
+    }
+    exponent as u32
+}
+
+// Sieve of Eratosthenes
+fn primes(limit: u32) -> Vec<u32> {
+    let limit = limit as usize;
+    let mut composite = vec![false; limit + 1];
+    let mut primes = vec![];
+
+    for number in 2..=limit {
+        if composite[number] {
+            continue;
+        }
+        primes.push(number as u32);
+        // usize is only 32 bits on wasm, where the square of a
+        // prime above 65535 would wrap around
+        let Some(square) = number.checked_mul(number)
+        else {
+            continue;
+        };
+        for multiple in (square..=limit).step_by(number) {
+            composite[multiple] = true;
+        }
+    }
+
+    primes
+}
+
+pub fn product_tree(
+    mut factors: Vec<BigUint>,
+    cancellation: &Cancellation,
+) -> Result<BigUint, SolverError> {
+    if factors.is_empty() {
+        return Ok(BigUint::from(1u32));
+    }
+
+    while factors.len() > 1 {
+        cancellation.check()?;
+        factors = factors
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => left * right,
+                [single] => single.clone(),
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+
+    Ok(factors.pop().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binomial_matches_pascals_triangle() {
+        let cancellation = Cancellation::new();
+        let mut row = vec![BigUint::from(1u32)];
+        for n in 1..=60u32 {
+            let mut next =
+                vec![BigUint::from(1u32); row.len() + 1];
+            for k in 1..row.len() {
+                next[k] = &row[k - 1] + &row[k];
+            }
+            row = next;
+            for (k, expected) in row.iter().enumerate() {
+                assert_eq!(
+                    &binomial(n, k as u32, &cancellation)
+                        .unwrap(),
+                    expected
+                );
+            }
+        }
+        assert_eq!(
+            binomial(5, 7, &cancellation).unwrap(),
+            BigUint::from(0u32)
+        );
+    }
+
+    #[test]
+    fn binomial_of_a_large_n() {
+        // The sieve squares the primes, which would overflow a 32-bit
+        // usize here
+        let n = 100_000u64;
+        assert_eq!(
+            binomial(n as u32, 3, &Cancellation::new())
+                .unwrap(),
+            BigUint::from(n * (n - 1) * (n - 2) / 6)
+        );
+        assert_eq!(
+            primes(30),
+            [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::SolverError;
+
+// Shared flag that long running loops check between
+// iterations. Clones share the same flag, so one can be
+// handed to a solver while the other is kept to cancel it.
+// Only native callers can use it. In the app a job runs
+// synchronously in a web worker, which can't read anything
+// until it is done, so the app terminates the worker instead
+#[derive(Clone, Default)]
+pub struct Cancellation(Arc<AtomicBool>);
+
+impl Cancellation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn check(&self) -> Result<(), SolverError> {
+        if self.is_cancelled() {
+            Err(SolverError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use fraction::{GenericFraction, Zero};
+use num_bigint::BigUint;
+use num_rational::Ratio;
+use serde::{Deserialize, Serialize};
+use time::Duration;
+use web_time::Instant;
+
+use crate::{
+    binomial, took, validate_probability, Cancellation,
+    Partial, Progress, SolverError, Stage,
+};
+
+// Where the recurrence of distribution starts from
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub enum Start {
+    // The first k that is asked for, P(X = 0) = q^n unless the
+    // values are cut off, then only ever upwards. Falls back to
+    // the mode when q = 0, since every term would come out as
+    // zero
+    Zero,
+    // floor((n + 1) p), the largest term, then outwards in both
+    // directions so that both tails are built the same way
+    Mode,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Distribution {
+    pub experiments: u32,
+    pub probability: Ratio<BigUint>,
+    // k of the first value
+    pub first: u32,
+    // P(X = k) for every k from first on
+    pub values: Vec<GenericFraction<BigUint>>,
+    // Sum of the values, which is exactly 1 for a complete
+    // distribution unless the recurrence went wrong somewhere
+    pub sum: GenericFraction<BigUint>,
+    pub took: Duration,
+}
+
+impl Distribution {
+    // Whether the values cover every k from 0 to n
+    pub fn is_complete(&self) -> bool {
+        self.first == 0
+            && self.values.len()
+                == self.experiments as usize + 1
+    }
+
+    // np, exact unlike anything taken from the values
+    pub fn mean(&self) -> GenericFraction<BigUint> {
+        GenericFraction::Rational(
+            fraction::Sign::Plus,
+            &self.probability
+                * BigUint::from(self.experiments),
+        )
+    }
+
+    // npq, exact as well
+    pub fn variance(&self) -> GenericFraction<BigUint> {
+        let q = Ratio::from_integer(BigUint::from(1u32))
+            - &self.probability;
+        GenericFraction::Rational(
+            fraction::Sign::Plus,
+            &self.probability
+                * q
+                * BigUint::from(self.experiments),
+        )
+    }
+}
+
+// Every P(X = k) from a single binomial coefficient and the
+// ratio P(k + 1) / P(k) = (n - k) p / ((k + 1) q). All terms
+// share the denominator d^n, so only the numerators
+// C(n, k) a^k b^(n-k) are carried, and both
+// N(k + 1) = N(k) (n - k) a / ((k + 1) b) and
+// N(k - 1) = N(k) k b / ((n - k + 1) a) divide exactly.
+// Every numerator has about n digits, so for a large n only the k
+// within `deviations` standard deviations of the mode are
+// computed, None computes all of them. Progress is reported once
+// per finished k
+pub fn distribution(
+    experiments: u32,
+    positive_probability: Ratio<BigUint>,
+    start: Start,
+    deviations: Option<u32>,
+    progress: &dyn Fn(Partial),
+    cancellation: &Cancellation,
+) -> Result<Distribution, SolverError> {
+    let now = Instant::now();
+
+    validate_probability(&positive_probability)?;
+    let probability = positive_probability.clone();
+
+    let (positive_numer, prob_denom) =
+        positive_probability.into();
+    let negative_numer = &prob_denom - &positive_numer;
+
+    let n = experiments as u64;
+    let mode = ((n + 1) * &positive_numer / &prob_denom)
+        .try_into()
+        .unwrap_or(experiments)
+        .min(experiments);
+    let (first, last) = match deviations {
+        Some(deviations) => {
+            // floor(sqrt(npq)) + 1 is never below the standard
+            // deviation
+            let variance =
+                n * &positive_numer * &negative_numer
+                    / (&prob_denom * &prob_denom);
+            let deviation = u32::try_from(variance.sqrt())
+                .unwrap_or(u32::MAX)
+                .saturating_add(1);
+            let width =
+                deviation.saturating_mul(deviations);
+            (
+                mode.saturating_sub(width),
+                mode.saturating_add(width).min(experiments),
+            )
+        }
+        None => (0, experiments),
+    };
+    let start = match start {
+        Start::Zero if !negative_numer.is_zero() => first,
+        _ => mode,
+    };
+
+    let total = (last - first) as usize + 1;
+    let report = |done: usize| {
+        progress(Partial::new(
+            Stage::Distribution,
+            &Progress {
+                iteration: done,
+                total: Some(total),
+                value: None,
+            },
+            None,
+        ))
+    };
+
+    let mut numerators = vec![BigUint::zero(); total];
+    numerators[(start - first) as usize] =
+        binomial(experiments, start, cancellation)?
+            * positive_numer.pow(start)
+            * negative_numer.pow(experiments - start);
+    let mut done = 1;
+    report(done);
+
+    // Upwards q is never zero, since then start is n, and
+    // downwards p is never zero, since then start is 0
+    for outcomes in start..last {
+        cancellation.check()?;
+        let k = (outcomes - first) as usize;
+        numerators[k + 1] = &numerators[k]
+            * (experiments - outcomes)
+            * &positive_numer
+            / (&negative_numer * (outcomes + 1));
+        done += 1;
+        report(done);
+    }
+    for outcomes in (first + 1..=start).rev() {
+        cancellation.check()?;
+        let k = (outcomes - first) as usize;
+        numerators[k - 1] =
+            &numerators[k] * outcomes * &negative_numer
+                / (&positive_numer
+                    * (experiments - outcomes + 1));
+        done += 1;
+        report(done);
+    }
+
+    let denominator = prob_denom.pow(experiments);
+    let sum = Ratio::new(
+        numerators.iter().sum(),
+        denominator.clone(),
+    );
+    let values = numerators
+        .into_iter()
+        .map(|numerator| {
+            GenericFraction::Rational(
+                fraction::Sign::Plus,
+                Ratio::new_raw(
+                    numerator,
+                    denominator.clone(),
+                ),
+            )
+        })
+        .collect();
+
+    let elapsed = now.elapsed();
+
+    Ok(Distribution {
+        experiments,
+        probability,
+        first,
+        values,
+        sum: GenericFraction::Rational(
+            fraction::Sign::Plus,
+            sum,
+        ),
+        took: took(elapsed)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bernoulli;
+    use crate::tests::ratio;
+
+    #[test]
+    fn complete_distribution_is_every_term_and_sums_to_one()
+    {
+        let cancellation = Cancellation::new();
+        for start in [Start::Zero, Start::Mode] {
+            let distribution = distribution(
+                40,
+                ratio(3, 10),
+                start,
+                None,
+                &|_| {},
+                &cancellation,
+            )
+            .unwrap();
+            assert!(distribution.is_complete());
+            assert_eq!(
+                distribution.sum,
+                GenericFraction::from(1u32)
+            );
+            assert_eq!(
+                distribution.mean(),
+                GenericFraction::from(12u32)
+            );
+            assert_eq!(
+                distribution.variance(),
+                GenericFraction::new(42u32, 5u32)
+            );
+            for (k, value) in
+                distribution.values.iter().enumerate()
+            {
+                let exact = bernoulli(
+                    40,
+                    k as u32,
+                    ratio(3, 10),
+                    &cancellation,
+                )
+                .unwrap();
+                assert_eq!(value, &exact.probability);
+            }
+        }
+    }
+
+    #[test]
+    fn deviations_cut_the_values_off_around_the_mode() {
+        // The mode is floor(401 * 0.3) = 120 and floor(sqrt(84)) + 1 =
+        // 10, so two deviations keep 100 to 140
+        let cancellation = Cancellation::new();
+        let distribution = distribution(
+            400,
+            ratio(3, 10),
+            Start::Zero,
+            Some(2),
+            &|_| {},
+            &cancellation,
+        )
+        .unwrap();
+        assert!(!distribution.is_complete());
+        assert_eq!(distribution.first, 100);
+        assert_eq!(distribution.values.len(), 41);
+        for (index, value) in
+            distribution.values.iter().enumerate()
+        {
+            let exact = bernoulli(
+                400,
+                100 + index as u32,
+                ratio(3, 10),
+                &cancellation,
+            )
+            .unwrap();
+            assert_eq!(value, &exact.probability);
+        }
+    }
+
+    #[test]
+    fn cancelling_during_the_recurrence_stops_it() {
+        let cancellation = Cancellation::new();
+        let result = distribution(
+            1000,
+            ratio(1, 2),
+            Start::Zero,
+            None,
+            &|partial| {
+                if partial.iteration == 10 {
+                    cancellation.cancel();
+                }
+            },
+            &cancellation,
+        );
+        assert_eq!(
+            result.err(),
+            Some(SolverError::Cancelled)
+        );
+    }
+}
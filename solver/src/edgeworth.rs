@@ -0,0 +1,172 @@
+use fraction::GenericFraction;
+use num_bigint::BigUint;
+use num_rational::Ratio;
+use web_time::Instant;
+
+use crate::{
+    moivre_laplace, took, validate_outcomes,
+    validate_probability, Bounds, Settings, SolverError,
+    SolverResult,
+};
+
+type F = GenericFraction<BigUint>;
+
+// Local theorem with the Edgeworth expansion of the lattice
+// distribution, P(X = k) ~ φ(x) / σ * (1 + γ1/6 He3(x)
+// + γ2/24 He4(x) + γ1^2/72 He6(x)) with x = (k - np) / σ,
+// σ = sqrt(npq), skewness γ1 = (q - p) / σ and excess kurtosis
+// γ2 = (1 - 6pq) / npq. Order 0 is the plain local theorem, 1
+// adds the γ1 term and 2, or anything above, the γ2 and γ1^2
+// terms as well.
+//
+// Since γ1 has σ in its denominator, every odd power of x in its
+// term meets another σ, so the whole correction factor is
+// rational and only φ(x) / σ needs exp and sqrt
+pub fn edgeworth(
+    experiments: u32,
+    positive_outcomes: u32,
+    positive_probability: Ratio<BigUint>,
+    order: u32,
+    settings: Settings,
+) -> Result<SolverResult, SolverError> {
+    let now = Instant::now();
+
+    validate_outcomes(experiments, positive_outcomes)?;
+    validate_probability(&positive_probability)?;
+
+    let factor = correction(
+        experiments,
+        positive_outcomes,
+        &positive_probability,
+        order,
+    );
+
+    let local = moivre_laplace(
+        experiments,
+        positive_outcomes,
+        positive_probability,
+        settings,
+    )?;
+
+    // A negative factor flips which end of the bounds is lower
+    let bounds = local.bounds.map(|bounds| {
+        let (lower, upper) = (
+            bounds.lower * factor.clone(),
+            bounds.upper * factor.clone(),
+        );
+        if lower <= upper {
+            Bounds { lower, upper }
+        } else {
+            Bounds {
+                lower: upper,
+                upper: lower,
+            }
+        }
+    });
+
+    let elapsed = now.elapsed();
+
+    Ok(SolverResult {
+        probability: local.probability * factor,
+        took: took(elapsed)?,
+        iterations: local.iterations,
+        bounds,
+    })
+}
+
+// The bracket of edgeworth, with every term rewritten through
+// D = k - np and x^2 = D^2 / npq
+fn correction(
+    experiments: u32,
+    positive_outcomes: u32,
+    positive_probability: &Ratio<BigUint>,
+    order: u32,
+) -> F {
+    let positive_numer = positive_probability.numer();
+    let prob_denom = positive_probability.denom();
+    let negative_numer = prob_denom - positive_numer;
+
+    let fraction = |numer: BigUint, denom: BigUint| {
+        F::new(numer, denom)
+    };
+    let whole = |value: u32| F::from(value);
+
+    let prob_denom_sq = prob_denom * prob_denom;
+    let pq = fraction(
+        positive_numer * &negative_numer,
+        prob_denom_sq.clone(),
+    );
+    let npq = pq.clone() * whole(experiments);
+    let distance = whole(positive_outcomes)
+        - fraction(
+            experiments * positive_numer,
+            prob_denom.clone(),
+        );
+    let skew = fraction(negative_numer, prob_denom.clone())
+        - fraction(
+            positive_numer.clone(),
+            prob_denom.clone(),
+        );
+
+    let x_sq =
+        distance.clone() * distance.clone() / npq.clone();
+    let x_4 = x_sq.clone() * x_sq.clone();
+    let x_6 = x_4.clone() * x_sq.clone();
+
+    let mut factor = whole(1);
+    if order >= 1 {
+        // γ1/6 He3(x) = (q - p) D (x^2 - 3) / 6npq
+        factor += skew.clone()
+            * distance
+            * (x_sq.clone() - whole(3))
+            / (whole(6) * npq.clone());
+    }
+    if order >= 2 {
+        // γ2/24 He4(x) = (1 - 6pq) (x^4 - 6x^2 + 3) / 24npq
+        let kurtosis = (whole(1) - whole(6) * pq)
+            * (x_4.clone() - whole(6) * x_sq.clone()
+                + whole(3))
+            / (whole(24) * npq.clone());
+        // γ1^2/72 He6(x) = (q - p)^2 (x^6 - 15x^4 + 45x^2 - 15)
+        // / 72npq
+        let skew_sq = skew.clone()
+            * skew
+            * (x_6 - whole(15) * x_4 + whole(45) * x_sq
+                - whole(15))
+            / (whole(72) * npq);
+        factor += kurtosis + skew_sq;
+    }
+
+    factor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ratio, relative_error, settings};
+    use crate::{bernoulli, Cancellation};
+
+    #[test]
+    fn higher_orders_get_closer_to_the_exact_value() {
+        let cancellation = Cancellation::new();
+        let exact =
+            bernoulli(100, 25, ratio(3, 10), &cancellation)
+                .unwrap()
+                .probability;
+        let errors: Vec<_> = (0..=2)
+            .map(|order| {
+                let result = edgeworth(
+                    100,
+                    25,
+                    ratio(3, 10),
+                    order,
+                    settings(&cancellation),
+                )
+                .unwrap();
+                relative_error(&result.probability, &exact)
+            })
+            .collect();
+        assert!(errors[1] < errors[0]);
+        assert!(errors[2] < errors[1]);
+    }
+}
@@ -0,0 +1,111 @@
+use fraction::{GenericFraction, Zero};
+use num_bigint::BigUint;
+use num_rational::Ratio;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    sqrt_bounds, sqrt_to_digits, validate_probability,
+    Bounds, Cancellation, SolverError,
+};
+
+// Berry-Esseen constant for sums of identically distributed
+// variables, C <= 0.4748 (Shevtsova, 2011)
+const BERRY_ESSEEN_NUMER: u32 = 4748;
+const BERRY_ESSEEN_DENOM: u32 = 10000;
+
+// Error guarantees that hold for a given n and p before anything
+// is computed. Every field encloses the exact value of its
+// formula, the square root being the only part that isn't exact
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ErrorBounds {
+    // Bound on sup |P(X <= x) - Φ((x - np) / sqrt(npq))| over all
+    // x, C E|B - p|^3 / (σ^3 sqrt(n)) = C (p^2 + q^2) / sqrt(npq)
+    // for a single Bernoulli trial B
+    pub berry_esseen: Bounds,
+    // Any P(a <= X <= b) is the difference of two values of the
+    // distribution function, so the integral theorem is off by at
+    // most twice berry_esseen
+    pub interval: Bounds,
+    // 1 / sqrt(npq), the order of the relative error of the local
+    // theorem for k within a few standard deviations of np. Only
+    // the order is known, there is no explicit constant to go
+    // with it
+    pub local_order: Bounds,
+}
+
+// digits is how many digits the square root of npq is computed
+// to, which is also about how tight the enclosures are
+pub fn error_bounds(
+    experiments: u32,
+    positive_probability: Ratio<BigUint>,
+    digits: usize,
+    cancellation: &Cancellation,
+) -> Result<ErrorBounds, SolverError> {
+    validate_probability(&positive_probability)?;
+
+    let (positive_numer, prob_denom) =
+        positive_probability.into();
+    let negative_numer = &prob_denom - &positive_numer;
+
+    // npq = npq_numer / prob_denom^2
+    let npq_numer =
+        experiments * &positive_numer * &negative_numer;
+    let npq_denom = &prob_denom * &prob_denom;
+    if npq_numer.is_zero() {
+        return Err(SolverError::DegenerateVariance);
+    }
+
+    let (guess_numer, guess_denom) = sqrt_to_digits(
+        npq_numer.clone(),
+        npq_denom.clone(),
+        digits,
+        &|_| {},
+        cancellation,
+    )?;
+    let (root_lower, root_upper) = sqrt_bounds(
+        (npq_numer.clone(), npq_denom.clone()),
+        (npq_numer, npq_denom.clone()),
+        (&guess_numer, &guess_denom),
+    );
+
+    // 1 / sqrt(npq), the smaller root gives the upper end
+    let local_order = (
+        Ratio::new(root_upper.1, root_upper.0),
+        Ratio::new(root_lower.1, root_lower.0),
+    );
+
+    // C (p^2 + q^2)
+    let factor = Ratio::new(
+        BERRY_ESSEEN_NUMER
+            * (positive_numer.pow(2)
+                + negative_numer.pow(2)),
+        BERRY_ESSEEN_DENOM * npq_denom,
+    );
+    let berry_esseen = (
+        &factor * &local_order.0,
+        &factor * &local_order.1,
+    );
+    let two = Ratio::from(BigUint::from(2u32));
+    let interval =
+        (&two * &berry_esseen.0, &two * &berry_esseen.1);
+
+    let bounds = |(lower, upper): (
+        Ratio<BigUint>,
+        Ratio<BigUint>,
+    )| Bounds {
+        lower: GenericFraction::Rational(
+            fraction::Sign::Plus,
+            lower,
+        ),
+        upper: GenericFraction::Rational(
+            fraction::Sign::Plus,
+            upper,
+        ),
+    };
+
+    Ok(ErrorBounds {
+        berry_esseen: bounds(berry_esseen),
+        interval: bounds(interval),
+        local_order: bounds(local_order),
+    })
+}
@@ -1,77 +1,473 @@
 use num_bigint::BigUint;
 
-use fraction::{BigFraction, GenericFraction};
+use fraction::{GenericFraction, Zero};
 
 use num_integer::Integer;
 use num_rational::Ratio;
 use serde::{Deserialize, Serialize};
 use time::Duration;
 
+mod binomial;
+mod cancel;
+mod distribution;
+mod edgeworth;
+mod error_bounds;
+mod method;
+mod pi;
+mod progress;
+mod saddle_point;
+mod stirling;
+mod sweep;
+
+pub use binomial::binomial;
+pub use cancel::Cancellation;
+pub use distribution::{distribution, Distribution, Start};
+pub use edgeworth::edgeworth;
+pub use error_bounds::{error_bounds, ErrorBounds};
+pub use method::{Method, Query};
+pub use pi::{pi, Pi};
+pub use progress::{Partial, Progress, Stage};
+pub use saddle_point::{
+    saddle_point, saddle_point_interval,
+};
+pub use stirling::stirling;
+pub use sweep::{
+    log10, sweep, Outcomes, Steps, Sweep, SweepPoint,
+};
+
 #[derive(Default, Clone, Serialize, Deserialize)]
 pub struct SolverResult {
     pub probability: GenericFraction<BigUint>,
     pub took: Duration,
     pub iterations: u32,
+    // Guaranteed enclosure of the value the method approximates, when the method can provide one
+    pub bounds: Option<Bounds>,
+}
+
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct Bounds {
+    pub lower: GenericFraction<BigUint>,
+    pub upper: GenericFraction<BigUint>,
+}
+
+impl Bounds {
+    // Amount of significant digits shared by everything inside the bounds, None if they are exact
+    pub fn accurate_digits(&self) -> Option<usize> {
+        let width = self.upper.clone() - self.lower.clone();
+        let (
+            GenericFraction::Rational(_, upper),
+            GenericFraction::Rational(_, width),
+        ) = (&self.upper, &width)
+        else {
+            return Some(0);
+        };
+
+        if width.is_zero() {
+            return None;
+        }
+
+        // With upper / width = v * 10^-shift for some 1 <= v < 10, all digits up to the shift
+        // are the same across the bounds
+        let (_, shift) = leading_digits(
+            &(upper.numer() * width.denom()),
+            &(upper.denom() * width.numer()),
+            1,
+        );
+
+        Some((-shift).max(0) as usize)
+    }
 }
 
 use web_time::Instant;
 
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum SolverError {
+    OutcomesExceedExperiments {
+        experiments: u32,
+        outcomes: u32,
+    },
+    ZeroDenominator,
+    ProbabilityAboveOne {
+        numer: BigUint,
+        denom: BigUint,
+    },
+    // p is either 0 or 1, so npq is 0 and the normal approximation has nothing to work with
+    DegenerateVariance,
+    DurationOverflow,
+    Cancelled,
+    // The method only approximates P(X = k), not P(from <= X <= to)
+    IntervalUnsupported,
+    // A series with no terms sums to 0, which the methods go on to divide by
+    NoIterations,
+}
+
+impl std::fmt::Display for SolverError {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            SolverError::OutcomesExceedExperiments {
+                experiments,
+                outcomes,
+            } => write!(
+                f,
+                "Required outcomes ({}) can't exceed the amount of experiments ({})",
+                outcomes, experiments
+            ),
+            SolverError::ZeroDenominator => {
+                write!(f, "Probability denominator can't be zero")
+            }
+            SolverError::ProbabilityAboveOne { numer, denom } => {
+                write!(
+                    f,
+                    "Probability {}/{} is above one",
+                    numer, denom
+                )
+            }
+            SolverError::DegenerateVariance => write!(
+                f,
+                "npq is zero, the approximation is undefined for p = 0 or p = 1"
+            ),
+            SolverError::DurationOverflow => {
+                write!(f, "Computation took too long to measure")
+            }
+            SolverError::Cancelled => {
+                write!(f, "Computation was cancelled")
+            }
+            SolverError::IntervalUnsupported => write!(
+                f,
+                "This method only approximates a single outcome, not an interval"
+            ),
+            SolverError::NoIterations => write!(
+                f,
+                "The series needs at least one iteration"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SolverError {}
+
+fn validate_outcomes(
+    experiments: u32,
+    outcomes: u32,
+) -> Result<(), SolverError> {
+    if outcomes > experiments {
+        return Err(
+            SolverError::OutcomesExceedExperiments {
+                experiments,
+                outcomes,
+            },
+        );
+    }
+    Ok(())
+}
+
+fn validate_probability(
+    probability: &FR,
+) -> Result<(), SolverError> {
+    if probability.denom().is_zero() {
+        return Err(SolverError::ZeroDenominator);
+    }
+    if probability.numer() > probability.denom() {
+        return Err(SolverError::ProbabilityAboveOne {
+            numer: probability.numer().clone(),
+            denom: probability.denom().clone(),
+        });
+    }
+    Ok(())
+}
+
+fn validate_iterations(
+    iterations: Iterations,
+) -> Result<(), SolverError> {
+    if let Iterations::Fixed(0) = iterations {
+        return Err(SolverError::NoIterations);
+    }
+    Ok(())
+}
+
+fn took(
+    elapsed: std::time::Duration,
+) -> Result<Duration, SolverError> {
+    Ok(Duration::microseconds(
+        elapsed
+            .as_micros()
+            .try_into()
+            .map_err(|_| SolverError::DurationOverflow)?,
+    ))
+}
+
 pub fn bernoulli(
     experiments: u32,
     positive_outcomes: u32,
     positive_probability: FR,
-) -> SolverResult {
+    cancellation: &Cancellation,
+) -> Result<SolverResult, SolverError> {
     let now = Instant::now();
 
+    validate_outcomes(experiments, positive_outcomes)?;
+    validate_probability(&positive_probability)?;
+
     let (positive_numer, prob_denom) =
         positive_probability.into();
 
     let negative_numer = &prob_denom - &positive_numer;
 
-    // Picking the biggest of the two factorials in the denumenator of combinations
-    let experiment_diff = (experiments - positive_outcomes)
-        .max(positive_outcomes);
-
-    // Simplifying the factorial in the numerator of combinations
-    let combinations_numer = (experiment_diff + 1
-        ..=experiments)
-        .map(BigUint::from)
-        .product::<BigUint>();
-
-    // Computing the remaining factorial in the denominator
-    let combinations_denom = (1..=(experiments
-        - experiment_diff))
-        .map(BigUint::from)
-        .product::<BigUint>();
-
     let negative_pow = experiments - positive_outcomes;
 
     // Multiplying combinations, p^k, q^n-k together
     let probability = Ratio::new_raw(
-        combinations_numer
-            * positive_numer.pow(positive_outcomes)
+        binomial(
+            experiments,
+            positive_outcomes,
+            cancellation,
+        )? * positive_numer.pow(positive_outcomes)
             * negative_numer.pow(negative_pow),
-        combinations_denom
-            * prob_denom.pow(positive_outcomes)
+        prob_denom.pow(positive_outcomes)
             * prob_denom.pow(negative_pow),
     );
 
     let elapsed = now.elapsed();
 
-    SolverResult {
+    Ok(SolverResult {
         probability: GenericFraction::Rational(
             fraction::Sign::Plus,
             probability,
         ),
-        took: Duration::microseconds(
-            elapsed.as_micros().try_into().unwrap(),
+        took: took(elapsed)?,
+        iterations: 0,
+        bounds: None,
+    })
+}
+
+pub fn bernoulli_at_least(
+    experiments: u32,
+    positive_outcomes: u32,
+    positive_probability: FR,
+    cancellation: &Cancellation,
+) -> Result<SolverResult, SolverError> {
+    bernoulli_interval(
+        experiments,
+        positive_outcomes,
+        experiments,
+        positive_probability,
+        cancellation,
+    )
+}
+
+pub fn bernoulli_at_most(
+    experiments: u32,
+    positive_outcomes: u32,
+    positive_probability: FR,
+    cancellation: &Cancellation,
+) -> Result<SolverResult, SolverError> {
+    bernoulli_interval(
+        experiments,
+        0,
+        positive_outcomes,
+        positive_probability,
+        cancellation,
+    )
+}
+
+// Exact probability of the amount of positive outcomes landing in from..=to
+pub fn bernoulli_interval(
+    experiments: u32,
+    from: u32,
+    to: u32,
+    positive_probability: FR,
+    cancellation: &Cancellation,
+) -> Result<SolverResult, SolverError> {
+    let now = Instant::now();
+
+    validate_probability(&positive_probability)?;
+
+    let to = to.min(experiments);
+
+    let (positive_numer, prob_denom) =
+        positive_probability.into();
+
+    let negative_numer = &prob_denom - &positive_numer;
+
+    let probability = if from > to {
+        Ratio::new_raw(0u32.into(), 1u32.into())
+    } else {
+        // Every term C(n, k) * p^k * q^n-k shares the denominator d^n, so only the numerators
+        // need to be summed. With m = to - from the sum is
+        // a^from * b^(n-to) * sum(C(n, from + j) * a^j * b^(m-j)) which is accumulated Horner
+        // style, so that q only ever gets multiplied in
+        let mut combinations =
+            binomial(experiments, from, cancellation)?;
+        let mut positive_pow = BigUint::from(1u32);
+        let mut sum = combinations.clone();
+
+        for outcomes in from..to {
+            cancellation.check()?;
+            // C(n, k + 1) = C(n, k) * (n - k) / (k + 1), the division is always exact
+            combinations = combinations
+                * (experiments - outcomes)
+                / (outcomes + 1);
+            positive_pow *= &positive_numer;
+            sum = sum * &negative_numer
+                + &combinations * &positive_pow;
+        }
+
+        Ratio::new_raw(
+            sum * positive_numer.pow(from)
+                * negative_numer.pow(experiments - to),
+            prob_denom.pow(experiments),
+        )
+    };
+
+    let elapsed = now.elapsed();
+
+    Ok(SolverResult {
+        probability: GenericFraction::Rational(
+            fraction::Sign::Plus,
+            probability,
         ),
+        took: took(elapsed)?,
         iterations: 0,
-    }
+        bounds: None,
+    })
 }
 
 type FR = Ratio<BigUint>;
 
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub enum Iterations {
+    // Always sum exactly this many terms of a series
+    Fixed(usize),
+    // Keep adding terms until this many first significant digits of the partial sum stop
+    // changing between iterations
+    UntilStable(usize),
+    // Range reduced exp good to this many significant digits. The Laplace series has no such
+    // reduction and gets summed until that many digits are stable instead
+    Reduced(usize),
+}
+
+// How the approximations compute their series, square roots and pi, along with where their
+// progress goes and what can cancel them. Methods only read the parts they need
+#[derive(Clone, Copy)]
+pub struct Settings<'a> {
+    pub iterations: Iterations,
+    pub square_root: SquareRoot,
+    pub pi_digits: usize,
+    // Digits the methods with guaranteed bounds compute every part to, see stirling
+    pub digits: usize,
+    pub progress: &'a dyn Fn(Partial),
+    pub cancellation: &'a Cancellation,
+}
+
+// e^x as numer / denom, which is always at or below the real value
+pub struct Exp {
+    pub numer: BigUint,
+    pub denom: BigUint,
+    // Amount of series terms that were summed
+    pub iterations: usize,
+    // Guaranteed upper bound on e^x, if the method could provide one
+    pub upper: Option<(BigUint, BigUint)>,
+}
+
+impl Iterations {
+    pub fn exp(
+        self,
+        exponent_numer: BigUint,
+        exponent_denom: BigUint,
+        progress: &dyn Fn(Progress),
+        cancellation: &Cancellation,
+    ) -> Result<Exp, SolverError> {
+        let (numer, denom, iterations) = match self {
+            Iterations::Fixed(iterations) => {
+                let (numer, denom) = exp(
+                    exponent_numer.clone(),
+                    exponent_denom.clone(),
+                    iterations,
+                    progress,
+                    cancellation,
+                )?;
+                (numer, denom, iterations)
+            }
+            Iterations::UntilStable(stable_amount) => {
+                sum_until_stable(
+                    exponent_numer.clone(),
+                    exponent_denom.clone(),
+                    stable_amount,
+                    |iter| iter,
+                    progress,
+                    cancellation,
+                )?
+            }
+            Iterations::Reduced(digits) => {
+                return exp_reduced(
+                    exponent_numer,
+                    exponent_denom,
+                    digits,
+                    progress,
+                    cancellation,
+                );
+            }
+        };
+
+        let upper = exp_error(
+            &exponent_numer,
+            &exponent_denom,
+            iterations,
+        )
+        .map(|(error_numer, error_denom)| {
+            (
+                &numer * &error_denom
+                    + error_numer * &denom,
+                &denom * error_denom,
+            )
+        });
+
+        Ok(Exp {
+            numer,
+            denom,
+            iterations,
+            upper,
+        })
+    }
+
+    pub fn laplace_series(
+        self,
+        x_sq_numer: BigUint,
+        x_sq_denom: BigUint,
+        progress: &dyn Fn(Progress),
+        cancellation: &Cancellation,
+    ) -> Result<(BigUint, BigUint, usize), SolverError>
+    {
+        match self {
+            Iterations::Fixed(iterations) => {
+                let (numer, denom) = laplace_series(
+                    x_sq_numer,
+                    x_sq_denom,
+                    iterations,
+                    progress,
+                    cancellation,
+                )?;
+                Ok((numer, denom, iterations))
+            }
+            Iterations::UntilStable(digits)
+            | Iterations::Reduced(digits) => {
+                sum_until_stable(
+                    x_sq_numer,
+                    x_sq_denom,
+                    digits,
+                    |iter| 2 * iter + 1,
+                    progress,
+                    cancellation,
+                )
+            }
+        }
+    }
+}
+
 // fraction manipulations were done by hand for optimization reasons since the library likes to
 // reduce the fraction whenever possible which turned out to slow down the function by a
 // substantial margin
@@ -79,10 +475,21 @@ pub fn moivre_laplace(
     experiments: u32,
     positive_outcomes: u32,
     positive_probability: FR,
-    exponentiation_iterations: usize,
-    square_root_iterations: usize,
-) -> SolverResult {
+    settings: Settings,
+) -> Result<SolverResult, SolverError> {
     let now = Instant::now();
+    let Settings {
+        iterations: exponentiation_iterations,
+        square_root,
+        pi_digits,
+        progress,
+        cancellation,
+        ..
+    } = settings;
+
+    validate_outcomes(experiments, positive_outcomes)?;
+    validate_probability(&positive_probability)?;
+    validate_iterations(exponentiation_iterations)?;
 
     let experiments: BigUint = experiments.into();
     let positive_outcomes: BigUint =
@@ -95,6 +502,10 @@ pub fn moivre_laplace(
     let two_npq_numer = 2u32 * &np * negative_numer;
     let two_npq_denom = &prob_denom * &prob_denom;
 
+    if two_npq_numer.is_zero() {
+        return Err(SolverError::DegenerateVariance);
+    }
+
     // Since we need to subtract np from k, we also need to find the least common denominator and
     // scale numerators accordingly. Since denominator of k is equal to 1 we can just multiply it's
     // numerator by np's denominator and get the appropriate value
@@ -110,26 +521,92 @@ pub fn moivre_laplace(
     .pow(2)
         * &two_npq_denom;
 
-    let exp_denom = (&prob_denom).pow(2) * &two_npq_numer;
+    let exp_denom = prob_denom.pow(2) * &two_npq_numer;
 
-    let (exp_numer, exp_denom) = exp(
-        exp_numer,
-        exp_denom,
-        exponentiation_iterations,
-    );
+    let pi = pi(pi_digits);
 
-    // These big u128 numbers are an approximation of pi in fraction form
-    let root_numer =
-        two_npq_numer * 30_246_273_033_735_921u128;
-    let root_denom =
-        two_npq_denom * 9_627_687_726_852_338u128;
+    let root_numer = &two_npq_numer * &pi.numer;
+    let root_denom = &two_npq_denom * &pi.denom;
 
     // function sqrt returns values in order numer, denom. But since we need 1 over sqrt, we just
     // swap around the numer and denom
-    let (left_denom, left_numer) = sqrt(
+    let (left_denom, left_numer) = square_root.sqrt(
         root_numer,
         root_denom,
-        square_root_iterations,
+        &|step| {
+            progress(Partial::new(
+                Stage::SquareRoot,
+                &step,
+                None,
+            ))
+        },
+        cancellation,
+    )?;
+
+    // The root is done first, so that every partial sum of
+    // e^x already gives a partial probability
+    let Exp {
+        numer: exp_numer,
+        denom: exp_denom,
+        iterations,
+        upper: exp_upper,
+    } = exponentiation_iterations.exp(
+        exp_numer,
+        exp_denom,
+        &|step| {
+            let probability =
+                step.value.map(|(numer, denom)| {
+                    Ratio::new_raw(
+                        &left_numer * denom,
+                        &left_denom * numer,
+                    )
+                });
+            progress(Partial::new(
+                Stage::Exp,
+                &step,
+                probability,
+            ))
+        },
+        cancellation,
+    )?;
+
+    // The real value is e^-x / sqrt(2π npq). Enclosing e^x, π and the root separately and then
+    // taking the opposite ends of them for each bound of 1 / (e^x * sqrt) gives a guaranteed
+    // interval for it
+    let (pi_lower, pi_upper) = (pi.lower(), pi.upper());
+    let (root_lower, root_upper) = sqrt_bounds(
+        (
+            &two_npq_numer * pi_lower.0,
+            &two_npq_denom * pi_lower.1,
+        ),
+        (
+            &two_npq_numer * pi_upper.0,
+            &two_npq_denom * pi_upper.1,
+        ),
+        (&left_denom, &left_numer),
+    );
+
+    let upper = Ratio::new_raw(
+        &exp_denom * root_lower.1,
+        &exp_numer * root_lower.0,
+    );
+    // Without an upper end of e^x, when the series is too short
+    // to bound its remainder, there is no lower bound and so no
+    // bounds at all rather than a made up 0
+    let bounds = exp_upper.map(
+        |(exp_upper_numer, exp_upper_denom)| Bounds {
+            lower: GenericFraction::Rational(
+                fraction::Sign::Plus,
+                Ratio::new_raw(
+                    exp_upper_denom * root_upper.1,
+                    exp_upper_numer * root_upper.0,
+                ),
+            ),
+            upper: GenericFraction::Rational(
+                fraction::Sign::Plus,
+                upper,
+            ),
+        },
     );
 
     let probability = Ratio::new_raw(
@@ -139,15 +616,525 @@ pub fn moivre_laplace(
 
     let elapsed = now.elapsed();
 
-    SolverResult {
-        took: Duration::microseconds(
-            elapsed.as_micros().try_into().unwrap(),
+    Ok(SolverResult {
+        took: took(elapsed)?,
+        probability: GenericFraction::Rational(
+            fraction::Sign::Plus,
+            probability,
         ),
+        iterations: iterations as u32,
+        bounds,
+    })
+}
+
+// Integral theorem: P(from <= X <= to) ~ Φ(x2) - Φ(x1) where x = (k - np) / sqrt(npq) and Φ is
+// the Laplace function. Same as the local theorem, fractions are handled by hand
+pub fn moivre_laplace_integral(
+    experiments: u32,
+    from: u32,
+    to: u32,
+    positive_probability: FR,
+    settings: Settings,
+) -> Result<SolverResult, SolverError> {
+    let now = Instant::now();
+
+    validate_probability(&positive_probability)?;
+    validate_iterations(settings.iterations)?;
+
+    let to = to.min(experiments);
+
+    let (probability, iterations) = if from > to {
+        (GenericFraction::from(0u32), 0)
+    } else {
+        laplace_difference(
+            experiments,
+            2 * from as i64,
+            2 * to as i64,
+            positive_probability,
+            settings,
+        )?
+    };
+
+    let elapsed = now.elapsed();
+
+    Ok(SolverResult {
+        took: took(elapsed)?,
+        probability,
+        iterations: iterations as u32,
+        bounds: None,
+    })
+}
+
+// Normal approximation with the continuity correction, P(X = k) ~ Φ((k + 1/2 - np) / sqrt(npq)) -
+// Φ((k - 1/2 - np) / sqrt(npq)), which treats the bar of k as the area under the curve from
+// k - 1/2 to k + 1/2
+pub fn continuity_corrected(
+    experiments: u32,
+    positive_outcomes: u32,
+    positive_probability: FR,
+    settings: Settings,
+) -> Result<SolverResult, SolverError> {
+    validate_outcomes(experiments, positive_outcomes)?;
+
+    continuity_corrected_interval(
+        experiments,
+        positive_outcomes,
+        positive_outcomes,
+        positive_probability,
+        settings,
+    )
+}
+
+// P(from <= X <= to) ~ Φ((to + 1/2 - np) / sqrt(npq)) - Φ((from - 1/2 - np) / sqrt(npq))
+pub fn continuity_corrected_interval(
+    experiments: u32,
+    from: u32,
+    to: u32,
+    positive_probability: FR,
+    settings: Settings,
+) -> Result<SolverResult, SolverError> {
+    let now = Instant::now();
+
+    validate_probability(&positive_probability)?;
+    validate_iterations(settings.iterations)?;
+
+    let to = to.min(experiments);
+
+    let (probability, iterations) = if from > to {
+        (GenericFraction::from(0u32), 0)
+    } else {
+        laplace_difference(
+            experiments,
+            2 * from as i64 - 1,
+            2 * to as i64 + 1,
+            positive_probability,
+            settings,
+        )?
+    };
+
+    let elapsed = now.elapsed();
+
+    Ok(SolverResult {
+        took: took(elapsed)?,
+        probability,
+        iterations: iterations as u32,
+        bounds: None,
+    })
+}
+
+// Φ((upper - np) / sqrt(npq)) - Φ((lower - np) / sqrt(npq)) where the bounds are given in halves,
+// so that both the whole numbers of the integral theorem and the k +- 1/2 of the continuity
+// correction fit. Also returns the most iterations either series took
+fn laplace_difference(
+    experiments: u32,
+    lower_halves: i64,
+    upper_halves: i64,
+    positive_probability: FR,
+    settings: Settings,
+) -> Result<(GenericFraction<BigUint>, usize), SolverError>
+{
+    let Settings {
+        iterations,
+        square_root,
+        pi_digits,
+        progress,
+        cancellation,
+        ..
+    } = settings;
+    let experiments: BigUint = experiments.into();
+    let (positive_numer, prob_denom) =
+        positive_probability.into();
+    let negative_numer = &prob_denom - &positive_numer;
+
+    let np = experiments * positive_numer;
+    // Numerator of npq, it's denominator is prob_denom^2
+    let npq = &np * negative_numer;
+
+    if npq.is_zero() {
+        return Err(SolverError::DegenerateVariance);
+    }
+
+    // 2np, to go with the bounds in halves
+    let two_np = 2u32 * &np;
+
+    let pi = pi(pi_digits);
+
+    let (left_denom, left_numer) = square_root.sqrt(
+        2u32 * &npq * pi.numer,
+        &prob_denom * &prob_denom * pi.denom,
+        &|step| {
+            progress(Partial::new(
+                Stage::SquareRoot,
+                &step,
+                None,
+            ))
+        },
+        cancellation,
+    )?;
+
+    let laplace = |halves: i64| {
+        // 2(k - np) scaled by the probability denominator, with the sign kept separately since
+        // we are working with unsigned numbers
+        let scaled_outcomes =
+            BigUint::from(halves.unsigned_abs())
+                * &prob_denom;
+        let (distance, sign) = if halves < 0 {
+            (
+                scaled_outcomes + &two_np,
+                fraction::Sign::Minus,
+            )
+        } else if scaled_outcomes >= two_np {
+            (
+                scaled_outcomes - &two_np,
+                fraction::Sign::Plus,
+            )
+        } else {
+            (
+                &two_np - scaled_outcomes,
+                fraction::Sign::Minus,
+            )
+        };
+
+        // x^2 = (k - np)^2 / npq = distance^2 / 4npq, the prob_denom^2 of both of them cancels
+        // out
+        let x_sq_numer = distance.pow(2);
+        let x_sq_denom = 4u32 * &npq;
+
+        let Exp {
+            numer: exp_numer,
+            denom: exp_denom,
+            iterations: exp_iterations,
+            ..
+        } = iterations.exp(
+            x_sq_numer.clone(),
+            2u32 * &x_sq_denom,
+            &|step| {
+                progress(Partial::new(
+                    Stage::Exp,
+                    &step,
+                    None,
+                ))
+            },
+            cancellation,
+        )?;
+        let (series_numer, series_denom, series_iterations) =
+            iterations.laplace_series(
+                x_sq_numer,
+                x_sq_denom,
+                &|step| {
+                    progress(Partial::new(
+                        Stage::LaplaceSeries,
+                        &step,
+                        None,
+                    ))
+                },
+                cancellation,
+            )?;
+
+        // Φ(x) = |k - np| / sqrt(2π npq) * e^(-x^2/2) * series
+        let laplace = GenericFraction::Rational(
+            sign,
+            Ratio::new_raw(
+                distance
+                    * exp_denom
+                    * series_numer
+                    * &left_numer,
+                2u32 * &prob_denom
+                    * exp_numer
+                    * series_denom
+                    * &left_denom,
+            ),
+        );
+
+        Ok((laplace, exp_iterations.max(series_iterations)))
+    };
+
+    let (upper, upper_iterations) = laplace(upper_halves)?;
+    let (lower, lower_iterations) = laplace(lower_halves)?;
+    Ok((
+        upper - lower,
+        upper_iterations.max(lower_iterations),
+    ))
+}
+
+// Series part of the Laplace function, Φ(x) = 1/sqrt(2π) * e^(-x^2/2) * sum(x^(2n+1) / (2n+1)!!).
+// Unlike the usual Taylor series of the integral this one has no alternating signs, so it can be
+// summed over unsigned numbers. Returns sum((x^2)^n / (2n+1)!!) for n < iterations, x itself is
+// left for the caller to multiply in. Since (2n+1)!! >= 2^n * n! this converges at least as fast
+// as exp(x^2/2), which is why both share the iteration count
+pub fn laplace_series(
+    x_sq_numer: BigUint,
+    x_sq_denom: BigUint,
+    iterations: usize,
+    progress: &dyn Fn(Progress),
+    cancellation: &Cancellation,
+) -> Result<(BigUint, BigUint), SolverError> {
+    if iterations == 0 {
+        return Ok((0u32.into(), 1u32.into()));
+    }
+
+    // All terms are kept over the common denominator x_sq_denom^n * (2n+1)!!, so moving to the
+    // next term only requires scaling the accumulated numerator
+    let (mut acc_numer, mut acc_denom) =
+        (BigUint::from(1u32), BigUint::from(1u32));
+    let mut x_sq_pow = BigUint::from(1u32);
+    for iter in 1..iterations {
+        cancellation.check()?;
+        let scale = &x_sq_denom * (2 * iter as u32 + 1);
+        x_sq_pow *= &x_sq_numer;
+        acc_numer = acc_numer * &scale + &x_sq_pow;
+        acc_denom *= scale;
+
+        if (iter + 1).is_power_of_two() {
+            progress(Progress {
+                iteration: iter + 1,
+                total: Some(iterations),
+                value: Some((&acc_numer, &acc_denom)),
+            });
+        }
+    }
+
+    Ok((acc_numer, acc_denom))
+}
+
+// Poisson approximation: P(X = k) ~ λ^k * e^-λ / k! with λ = np
+pub fn poisson(
+    experiments: u32,
+    positive_outcomes: u32,
+    positive_probability: FR,
+    settings: Settings,
+) -> Result<SolverResult, SolverError> {
+    validate_outcomes(experiments, positive_outcomes)?;
+
+    poisson_interval(
+        experiments,
+        positive_outcomes,
+        positive_outcomes,
+        positive_probability,
+        settings,
+    )
+}
+
+// Neither the square root nor pi is needed here, only the iterations of the settings are used
+pub fn poisson_interval(
+    experiments: u32,
+    from: u32,
+    to: u32,
+    positive_probability: FR,
+    settings: Settings,
+) -> Result<SolverResult, SolverError> {
+    let now = Instant::now();
+    let Settings {
+        iterations: exponentiation_iterations,
+        progress,
+        cancellation,
+        ..
+    } = settings;
+
+    validate_probability(&positive_probability)?;
+    validate_iterations(exponentiation_iterations)?;
+
+    let (positive_numer, prob_denom) =
+        positive_probability.into();
+
+    let lambda_numer =
+        BigUint::from(experiments) * positive_numer;
+
+    let (probability, iterations) = if from > to {
+        (Ratio::new_raw(0u32.into(), 1u32.into()), 0)
+    } else {
+        // sum(λ^k / k!) for k in from..=to is λ^from / from! * sum(λ^j / ((from+1)...(from+j))),
+        // the inner sum is accumulated Horner style from the innermost term outwards
+        let (mut sum_numer, mut sum_denom) =
+            (BigUint::from(1u32), BigUint::from(1u32));
+        for outcomes in (from + 1..=to).rev() {
+            cancellation.check()?;
+            let scale = &prob_denom * outcomes;
+            sum_numer = &sum_denom * &scale
+                + &lambda_numer * sum_numer;
+            sum_denom *= scale;
+        }
+
+        let sum_numer = lambda_numer.pow(from) * sum_numer;
+        let sum_denom = prob_denom.pow(from)
+            * factorial_new(from.into())
+            * sum_denom;
+
+        // Same as with exp, e^-λ is just e^λ with numerator and denominator swapped
+        let Exp {
+            numer: exp_numer,
+            denom: exp_denom,
+            iterations,
+            ..
+        } = exponentiation_iterations.exp(
+            lambda_numer,
+            prob_denom,
+            &|step| {
+                let probability =
+                    step.value.map(|(numer, denom)| {
+                        Ratio::new_raw(
+                            &sum_numer * denom,
+                            &sum_denom * numer,
+                        )
+                    });
+                progress(Partial::new(
+                    Stage::Exp,
+                    &step,
+                    probability,
+                ))
+            },
+            cancellation,
+        )?;
+
+        (
+            Ratio::new_raw(
+                sum_numer * exp_denom,
+                sum_denom * exp_numer,
+            ),
+            iterations,
+        )
+    };
+
+    let elapsed = now.elapsed();
+
+    Ok(SolverResult {
         probability: GenericFraction::Rational(
             fraction::Sign::Plus,
             probability,
         ),
-        iterations: 0,
+        took: took(elapsed)?,
+        iterations: iterations as u32,
+        bounds: None,
+    })
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub enum SquareRoot {
+    // Fixed amount of Newton steps, each of them doubles the size of the numbers
+    Iterations(usize),
+    // Newton steps until the result is provably within 10^-digits of the real root
+    Digits(usize),
+}
+
+impl SquareRoot {
+    pub fn sqrt(
+        self,
+        target_numer: BigUint,
+        target_denom: BigUint,
+        progress: &dyn Fn(Progress),
+        cancellation: &Cancellation,
+    ) -> Result<(BigUint, BigUint), SolverError> {
+        match self {
+            SquareRoot::Iterations(iterations) => sqrt(
+                target_numer,
+                target_denom,
+                iterations,
+                progress,
+                cancellation,
+            ),
+            SquareRoot::Digits(digits) => sqrt_to_digits(
+                target_numer,
+                target_denom,
+                digits,
+                progress,
+                cancellation,
+            ),
+        }
+    }
+}
+
+// Sums series of the form sum(x^i / (c(1) * c(2) * ... * c(i))), like exp where c(i) = i, until
+// the first stable_amount significant digits of the partial sum stop changing. The partial sum is
+// kept over the common denominator x_denom^i * c(1) * ... * c(i), so every iteration only needs to
+// scale the accumulated numerator and add the next power of x_numer
+fn sum_until_stable(
+    x_numer: BigUint,
+    x_denom: BigUint,
+    stable_amount: usize,
+    coefficient: impl Fn(u32) -> u32,
+    progress: &dyn Fn(Progress),
+    cancellation: &Cancellation,
+) -> Result<(BigUint, BigUint, usize), SolverError> {
+    let (mut acc_numer, mut acc_denom) =
+        (BigUint::from(1u32), BigUint::from(1u32));
+    let mut x_pow = BigUint::from(1u32);
+    let mut previous = leading_digits(
+        &acc_numer,
+        &acc_denom,
+        stable_amount,
+    );
+
+    let mut iter = 1;
+    loop {
+        cancellation.check()?;
+        let scale = &x_denom * coefficient(iter);
+        x_pow *= &x_numer;
+        acc_numer = acc_numer * &scale + &x_pow;
+        acc_denom *= scale;
+        iter += 1;
+
+        // Reporting every step would cost about as much as
+        // the step itself, powers of two keep it logarithmic
+        if iter.is_power_of_two() {
+            progress(Progress {
+                iteration: iter as usize,
+                total: None,
+                value: Some((&acc_numer, &acc_denom)),
+            });
+        }
+
+        let current = leading_digits(
+            &acc_numer,
+            &acc_denom,
+            stable_amount,
+        );
+        if current == previous {
+            break;
+        }
+        previous = current;
+    }
+
+    Ok((acc_numer, acc_denom, iter as usize))
+}
+
+// First `amount` significant digits of numer / denom as an integer, along with the power of ten
+// they had to be scaled by to get there
+pub fn leading_digits(
+    numer: &BigUint,
+    denom: &BigUint,
+    amount: usize,
+) -> (BigUint, i64) {
+    if numer.is_zero() {
+        return (BigUint::zero(), 0);
+    }
+
+    let amount = amount.max(1) as u32;
+    let lower = BigUint::from(10u32).pow(amount - 1);
+    let upper = &lower * 10u32;
+
+    // log10(2) ~ 0.30103 gives a close enough starting point, which gets corrected below
+    let mut shift = amount as i64
+        - (numer.bits() as i64 - denom.bits() as i64)
+            * 30103
+            / 100_000;
+    loop {
+        let digits = if shift >= 0 {
+            numer * BigUint::from(10u32).pow(shift as u32)
+                / denom
+        } else {
+            numer
+                / (denom
+                    * BigUint::from(10u32)
+                        .pow(-shift as u32))
+        };
+
+        if digits >= upper {
+            shift -= 1;
+        } else if digits < lower {
+            shift += 1;
+        } else {
+            return (digits, shift);
+        }
     }
 }
 
@@ -155,10 +1142,13 @@ pub fn sqrt(
     target_numer: BigUint,
     target_denom: BigUint,
     iterations: usize,
-) -> (BigUint, BigUint) {
+    progress: &dyn Fn(Progress),
+    cancellation: &Cancellation,
+) -> Result<(BigUint, BigUint), SolverError> {
     let (mut guess_top, mut guess_bot) =
         (target_numer.sqrt(), target_denom.sqrt());
-    for _ in 0..iterations {
+    for iteration in 1..=iterations {
+        cancellation.check()?;
         let inside_top = &target_numer * &guess_bot;
         let inside_bot = &target_denom * &guess_top;
         (guess_top, guess_bot) = add_ratios_raw_raw(
@@ -169,36 +1159,429 @@ pub fn sqrt(
         } else {
             guess_bot *= 2u32
         }
+        progress(Progress {
+            iteration,
+            total: Some(iterations),
+            value: Some((&guess_top, &guess_bot)),
+        });
     }
-    (guess_top, guess_bot)
+    Ok((guess_top, guess_bot))
 }
 
+// Newton's method where every iterate is rounded up to the fixed denominator 10^(digits+1), which
+// keeps the numbers from growing. Starting above the root, (g + t/g) / 2 >= sqrt(t) by AM-GM and
+// rounding up only moves it further up, so every iterate stays an upper bound. Because of that
+// t/g <= sqrt(t) <= g, and once g - t/g <= 10^-digits the result is within the tolerance
+pub fn sqrt_to_digits(
+    target_numer: BigUint,
+    target_denom: BigUint,
+    digits: usize,
+    progress: &dyn Fn(Progress),
+    cancellation: &Cancellation,
+) -> Result<(BigUint, BigUint), SolverError> {
+    let tolerance = BigUint::from(10u32).pow(digits as u32);
+    let scale = &tolerance * 10u32;
+
+    // isqrt(floor(t)) + 1 is always above sqrt(t)
+    let mut guess =
+        ((&target_numer / &target_denom).sqrt() + 1u32)
+            * &scale;
+    let scaled_target = &target_numer * &scale * &scale;
+
+    let mut iteration = 0;
+    loop {
+        cancellation.check()?;
+        progress(Progress {
+            iteration,
+            total: None,
+            value: Some((&guess, &scale)),
+        });
+        iteration += 1;
+
+        // (g^2 - t) / g <= 10^-digits, multiplied through by the denominators
+        let gap = &guess * &guess * &target_denom
+            - &scaled_target;
+        if gap * &tolerance
+            <= &guess * &scale * &target_denom
+        {
+            break;
+        }
+
+        // ceil((G^2 * t_denom + t_numer * D^2) / (2 * G * t_denom))
+        let next_numer = &guess * &guess * &target_denom
+            + &scaled_target;
+        let next_denom = 2u32 * &guess * &target_denom;
+        let next = next_numer.div_ceil(&next_denom);
+
+        // Can only happen when the tolerance is already met up to rounding
+        if next >= guess {
+            break;
+        }
+        guess = next;
+    }
+
+    Ok((guess, scale))
+}
+
+// Remainder of the Taylor series of e^x after `iterations` terms is
+// x^N/N! * (1 + x/(N+1) + x^2/(N+1)^2 + ...) <= x^N/N! * (N+1) / (N+1-x),
+// which only converges while x < N+1
+pub fn exp_error(
+    exponent_numer: &BigUint,
+    exponent_denom: &BigUint,
+    iterations: usize,
+) -> Option<(BigUint, BigUint)> {
+    let next = BigUint::from(iterations + 1);
+    let slack = &next * exponent_denom;
+    if slack <= *exponent_numer {
+        return None;
+    }
+
+    Some((
+        exponent_numer.pow(iterations as u32)
+            * next
+            * exponent_denom,
+        exponent_denom.pow(iterations as u32)
+            * factorial_new(iterations.into())
+            * (slack - exponent_numer),
+    ))
+}
+
+// For any guess g the real root lies between g and t/g, since their product is t. Takes the
+// bounds of t separately, so that an uncertain t (like one containing pi) can still be enclosed
+pub fn sqrt_bounds(
+    target_lower: (BigUint, BigUint),
+    target_upper: (BigUint, BigUint),
+    guess: (&BigUint, &BigUint),
+) -> ((BigUint, BigUint), (BigUint, BigUint)) {
+    let (guess_numer, guess_denom) = guess;
+
+    let lower_quotient = (
+        target_lower.0 * guess_denom,
+        target_lower.1 * guess_numer,
+    );
+    let upper_quotient = (
+        target_upper.0 * guess_denom,
+        target_upper.1 * guess_numer,
+    );
+
+    let guess = (guess_numer.clone(), guess_denom.clone());
+    let guess_below =
+        |(numer, denom): &(BigUint, BigUint)| {
+            &guess.0 * denom <= numer * &guess.1
+        };
+
+    let lower = if guess_below(&lower_quotient) {
+        guess.clone()
+    } else {
+        lower_quotient
+    };
+    let upper = if guess_below(&upper_quotient) {
+        upper_quotient
+    } else {
+        guess
+    };
+
+    (lower, upper)
+}
+
+// e^x = (e^(x/2^m))^(2^m), with m picked so that x/2^m <= 1. The Taylor series only needs a
+// handful of terms there and the squarings are done in fixed point over 2^bits, rounding the
+// lower bound down and the upper bound up, so the operands stay bounded while the result still
+// encloses the real value. Each squaring doubles the relative error, which the extra m bits of
+// precision make up for
+pub fn exp_reduced(
+    exponent_numer: BigUint,
+    exponent_denom: BigUint,
+    digits: usize,
+    progress: &dyn Fn(Progress),
+    cancellation: &Cancellation,
+) -> Result<Exp, SolverError> {
+    let halvings = (exponent_numer.bits() + 1)
+        .saturating_sub(exponent_denom.bits());
+    let reduced_denom = exponent_denom << halvings;
+
+    // log2(10) ~ 3.33 bits for every decimal digit
+    let bits = digits as u64 * 333 / 100 + halvings + 16;
+
+    // With x <= 1 the remainder after N terms is at most 2 / N!, so N! > 2^(bits+1) makes it
+    // smaller than the fixed point resolution
+    let mut iterations = 0;
+    let mut factorial = BigUint::from(1u32);
+    while factorial.bits() <= bits + 1 {
+        iterations += 1;
+        factorial *= iterations as u32;
+    }
+
+    let error = exp_error(
+        &exponent_numer,
+        &reduced_denom,
+        iterations,
+    )
+    .expect("x <= 1 always has a bounded remainder");
+    // Partial values here are of e^(x/2^m), which are no
+    // approximation of e^x, so only the step count is passed on
+    let total = iterations + halvings as usize;
+    let (numer, denom) = exp(
+        exponent_numer,
+        reduced_denom,
+        iterations,
+        &|step| {
+            progress(Progress {
+                iteration: step.iteration,
+                total: Some(total),
+                value: None,
+            })
+        },
+        cancellation,
+    )?;
+
+    let scaled_error =
+        (&error.0 << bits).div_ceil(&error.1);
+    let mut lower = (&numer << bits) / &denom;
+    let mut upper =
+        (numer << bits).div_ceil(&denom) + scaled_error;
+
+    for halving in 1..=halvings as usize {
+        cancellation.check()?;
+        lower = (&lower * &lower) >> bits;
+        upper = (&upper * &upper)
+            .div_ceil(&(BigUint::from(1u32) << bits));
+        progress(Progress {
+            iteration: iterations + halving,
+            total: Some(total),
+            value: None,
+        });
+    }
+
+    let denom = BigUint::from(1u32) << bits;
+
+    Ok(Exp {
+        numer: lower,
+        upper: Some((upper, denom.clone())),
+        denom,
+        iterations,
+    })
+}
+
+// Enclosure lower <= ln(numer / denom) <= upper for any positive fraction, good to `digits`
+// digits after the point. Below 1 it is ln(x) = -ln(1/x)
+pub(crate) fn ln(
+    numer: BigUint,
+    denom: BigUint,
+    digits: usize,
+    cancellation: &Cancellation,
+) -> Result<
+    (GenericFraction<BigUint>, GenericFraction<BigUint>),
+    SolverError,
+> {
+    if numer >= denom {
+        let (lower, upper, denom) = ln_above_one(
+            numer,
+            denom,
+            digits,
+            cancellation,
+        )?;
+        Ok((
+            GenericFraction::new(lower, denom.clone()),
+            GenericFraction::new(upper, denom),
+        ))
+    } else {
+        let (lower, upper, denom) = ln_above_one(
+            denom,
+            numer,
+            digits,
+            cancellation,
+        )?;
+        Ok((
+            -GenericFraction::new(upper, denom.clone()),
+            -GenericFraction::new(lower, denom),
+        ))
+    }
+}
+
+// ln(x) for x >= 1 enclosed as lower / denom <= ln(x) <= upper / denom. It is
+// ln(x) = m ln(2) + ln(y) with x = 2^m y and 1 <= y < 2, where both logarithms come from
+// ln(y) = 2 atanh((y - 1) / (y + 1)). With the argument of atanh at most 1/3 every term of its
+// series adds about a decimal digit, so a few terms per digit are enough
+fn ln_above_one(
+    numer: BigUint,
+    denom: BigUint,
+    digits: usize,
+    cancellation: &Cancellation,
+) -> Result<(BigUint, BigUint, BigUint), SolverError> {
+    let mut halvings =
+        numer.bits().saturating_sub(denom.bits());
+    if (&denom << halvings) > numer {
+        halvings -= 1;
+    }
+    let reduced_denom = denom << halvings;
+
+    // m ln(2) needs log2(m) more bits to keep the same absolute precision
+    let bits = digits as u64 * 333 / 100
+        + (64 - halvings.leading_zeros()) as u64
+        + 16;
+
+    let (reduced_lower, reduced_upper) = atanh_fixed(
+        &(&numer - &reduced_denom),
+        &(&numer + &reduced_denom),
+        bits,
+        cancellation,
+    )?;
+    // ln(2) = 2 atanh(1/3)
+    let (two_lower, two_upper) = atanh_fixed(
+        &BigUint::from(1u32),
+        &BigUint::from(3u32),
+        bits,
+        cancellation,
+    )?;
+
+    Ok((
+        2u32 * (reduced_lower + halvings * two_lower),
+        2u32 * (reduced_upper + halvings * two_upper),
+        BigUint::from(1u32) << bits,
+    ))
+}
+
+// atanh(z) = sum(z^(2i+1) / (2i+1)) for z = numer / denom <= 1/3, in fixed point over 2^bits.
+// Every power and term is rounded down, losing less than i + 2 units on the i-th term, and with
+// bits / 3 + 2 terms the remainder z^(2N+1) / ((2N+1)(1 - z^2)) is below one unit, so the sum
+// plus (N + 1)^2 + 1 units is above the real value
+fn atanh_fixed(
+    numer: &BigUint,
+    denom: &BigUint,
+    bits: u64,
+    cancellation: &Cancellation,
+) -> Result<(BigUint, BigUint), SolverError> {
+    let terms = bits / 3 + 2;
+    let numer_sq = numer * numer;
+    let denom_sq = denom * denom;
+
+    let mut power = (numer << bits) / denom;
+    let mut sum = BigUint::from(0u32);
+    for term in 0..terms {
+        cancellation.check()?;
+        sum += &power / (2 * term + 1);
+        power = power * &numer_sq / &denom_sq;
+    }
+
+    let slack =
+        BigUint::from((terms + 1) * (terms + 1) + 1);
+    Ok((sum.clone(), sum + slack))
+}
+
+// Sum of the first `iterations` terms of the Taylor series of e^x, computed with binary
+// splitting. The result is the same rational as summing term by term, but it is not reduced:
+// the denominator is always exponent_denom^(N-1) * (N-1)! for N iterations, the common
+// denominator of all the terms, instead of the running lcm of the terms summed so far
 pub fn exp(
     exponent_numer: BigUint,
     exponent_denom: BigUint,
     iterations: usize,
-) -> (BigUint, BigUint) {
-    let (mut acc_numer, mut acc_denum) =
-        (BigUint::from(0u32), BigUint::from(1u32));
-    for iter in 0..iterations {
-        (acc_numer, acc_denum) = add_ratios_raw_raw(
-            acc_numer,
-            acc_denum,
-            exponent_numer.pow(iter as u32),
-            exponent_denom.pow(iter as u32)
-                * factorial_new(iter.into()),
-        );
+    progress: &dyn Fn(Progress),
+    cancellation: &Cancellation,
+) -> Result<(BigUint, BigUint), SolverError> {
+    match iterations {
+        0 => Ok((0u32.into(), 1u32.into())),
+        1 => Ok((1u32.into(), 1u32.into())),
+        _ => {
+            // e^x = 1 + sum(prod(x / j for j in 1..=k) for k in 1..N)
+            let (_, denom, numer) = exp_split(
+                &exponent_numer,
+                &exponent_denom,
+                1,
+                iterations as u32,
+                &|step| {
+                    progress(Progress {
+                        total: Some(iterations),
+                        ..step
+                    })
+                },
+                cancellation,
+            )?;
+            Ok((&denom + numer, denom))
+        }
+    }
+}
+
+// For the terms from..to returns (P, Q, T) where P and Q are the products of x_numer and
+// x_denom * j over the range and T / Q = sum(prod(x / j for j in from..=k) for k in from..to).
+// Splitting the range in half and merging with T = T1 * Q2 + P1 * T2 keeps the multiplied
+// numbers balanced in size, which is what makes it fast
+fn exp_split(
+    x_numer: &BigUint,
+    x_denom: &BigUint,
+    from: u32,
+    to: u32,
+    progress: &dyn Fn(Progress),
+    cancellation: &Cancellation,
+) -> Result<(BigUint, BigUint, BigUint), SolverError> {
+    cancellation.check()?;
+
+    let (p, q, t) = if to - from == 1 {
+        (x_numer.clone(), x_denom * from, x_numer.clone())
+    } else {
+        let middle = from + (to - from) / 2;
+        let (left_p, left_q, left_t) = exp_split(
+            x_numer,
+            x_denom,
+            from,
+            middle,
+            progress,
+            cancellation,
+        )?;
+        let (right_p, right_q, right_t) = exp_split(
+            x_numer,
+            x_denom,
+            middle,
+            to,
+            progress,
+            cancellation,
+        )?;
+
+        (
+            &left_p * right_p,
+            left_q * &right_q,
+            left_t * right_q + left_p * right_t,
+        )
+    };
+
+    // Ranges that start at the first term are the partial
+    // sums of the series. The left halves always finish
+    // first, so those come in with a doubling amount of terms
+    if from == 1 {
+        let numer = &q + &t;
+        progress(Progress {
+            iteration: to as usize,
+            total: None,
+            value: Some((&numer, &q)),
+        });
     }
 
-    (acc_numer, acc_denum)
+    Ok((p, q, t))
 }
 
 pub fn factorial_new(base: BigUint) -> BigUint {
-    if base <= BigUint::from(1u32) {
-        BigUint::from(1u32)
-    } else {
-        factorial_new(&base - &BigUint::from(1u32)) * base
+    let base = u64::try_from(base).expect(
+        "factorials this large don't fit in memory",
+    );
+    range_product(1, base)
+}
+
+// Product of from..=to. Splitting the range in half keeps the multiplied numbers balanced in size
+// and the recursion only log2(to - from) deep, so large factorials neither crawl nor overflow the
+// stack
+fn range_product(from: u64, to: u64) -> BigUint {
+    if to < from {
+        return BigUint::from(1u32);
+    }
+    if to - from < 16 {
+        return (from..=to).map(BigUint::from).product();
     }
+
+    let middle = from + (to - from) / 2;
+    range_product(from, middle)
+        * range_product(middle + 1, to)
 }
 
 fn add_ratios_raw_raw(
@@ -217,3 +1600,348 @@ fn add_ratios_raw_raw(
 
     (lhs_numer + rhs_numer, common_denom)
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    // Exact value of a decimal like 0.25
+    pub(crate) fn decimal(
+        text: &str,
+    ) -> GenericFraction<BigUint> {
+        let (whole, fractional) =
+            text.split_once('.').unwrap_or((text, ""));
+        GenericFraction::new(
+            format!("{}{}", whole, fractional)
+                .parse::<BigUint>()
+                .unwrap(),
+            BigUint::from(10u32)
+                .pow(fractional.len() as u32),
+        )
+    }
+
+    pub(crate) fn ratio(numer: u32, denom: u32) -> FR {
+        Ratio::new(numer.into(), denom.into())
+    }
+
+    pub(crate) fn relative_error(
+        value: &GenericFraction<BigUint>,
+        exact: &GenericFraction<BigUint>,
+    ) -> GenericFraction<BigUint> {
+        let difference = value.clone() - exact.clone();
+        let difference =
+            if difference < GenericFraction::zero() {
+                -difference
+            } else {
+                difference
+            };
+        difference / exact.clone()
+    }
+
+    pub(crate) fn settings(
+        cancellation: &Cancellation,
+    ) -> Settings<'_> {
+        Settings {
+            iterations: Iterations::Fixed(300),
+            square_root: SquareRoot::Digits(40),
+            pi_digits: 40,
+            digits: 30,
+            progress: &|_| {},
+            cancellation,
+        }
+    }
+
+    #[test]
+    fn bernoulli_is_the_binomial_formula() {
+        // C(10, 3) / 2^10
+        let result = bernoulli(
+            10,
+            3,
+            ratio(1, 2),
+            &Cancellation::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            result.probability,
+            GenericFraction::new(120u32, 1024u32)
+        );
+    }
+
+    #[test]
+    fn bernoulli_interval_is_the_sum_of_its_terms() {
+        let cancellation = Cancellation::new();
+        let interval = bernoulli_interval(
+            30,
+            5,
+            12,
+            ratio(1, 3),
+            &cancellation,
+        )
+        .unwrap();
+        let sum = (5..=12)
+            .map(|k| {
+                bernoulli(30, k, ratio(1, 3), &cancellation)
+                    .unwrap()
+                    .probability
+            })
+            .fold(GenericFraction::zero(), |sum, term| {
+                sum + term
+            });
+        assert_eq!(interval.probability, sum);
+
+        let everything = bernoulli_interval(
+            50,
+            0,
+            50,
+            ratio(7, 10),
+            &cancellation,
+        )
+        .unwrap();
+        assert_eq!(
+            everything.probability,
+            GenericFraction::from(1u32)
+        );
+    }
+
+    #[test]
+    fn moivre_laplace_encloses_its_value() {
+        // At k = np the local theorem is 1 / sqrt(2π npq) = 1 / sqrt(50π)
+        // = 0.07978845608028653558...
+        let cancellation = Cancellation::new();
+        let result = moivre_laplace(
+            100,
+            50,
+            ratio(1, 2),
+            settings(&cancellation),
+        )
+        .unwrap();
+        let bounds = result.bounds.unwrap();
+        assert!(bounds.lower <= result.probability);
+        assert!(result.probability <= bounds.upper);
+        assert!(
+            bounds.lower <= decimal("0.07978845608028654")
+        );
+        assert!(
+            decimal("0.07978845608028653") <= bounds.upper
+        );
+    }
+
+    #[test]
+    fn too_short_a_series_leaves_moivre_laplace_unbounded()
+    {
+        // x = (30 - 50)^2 / 50 = 8, which 5 terms can't bound the
+        // remainder of e^x for
+        let cancellation = Cancellation::new();
+        let result = moivre_laplace(
+            100,
+            30,
+            ratio(1, 2),
+            Settings {
+                iterations: Iterations::Fixed(5),
+                ..settings(&cancellation)
+            },
+        )
+        .unwrap();
+        assert!(result.bounds.is_none());
+    }
+
+    #[test]
+    fn cancelled_before_a_call_fails_right_away() {
+        let cancellation = Cancellation::new();
+        cancellation.cancel();
+        assert_eq!(
+            bernoulli(100, 50, ratio(1, 2), &cancellation)
+                .err(),
+            Some(SolverError::Cancelled)
+        );
+        assert_eq!(
+            moivre_laplace(
+                100,
+                50,
+                ratio(1, 2),
+                settings(&cancellation)
+            )
+            .err(),
+            Some(SolverError::Cancelled)
+        );
+    }
+
+    #[test]
+    fn cancelling_during_exp_stops_the_series() {
+        let cancellation = Cancellation::new();
+        let result = Iterations::Fixed(1000).exp(
+            1u32.into(),
+            1u32.into(),
+            &|step| {
+                if step.iteration >= 10 {
+                    cancellation.cancel();
+                }
+            },
+            &cancellation,
+        );
+        assert_eq!(
+            result.err(),
+            Some(SolverError::Cancelled)
+        );
+    }
+
+    #[test]
+    fn continuity_correction_gets_closer_to_the_exact_interval(
+    ) {
+        let cancellation = Cancellation::new();
+        let exact = bernoulli_interval(
+            1000,
+            480,
+            520,
+            ratio(1, 2),
+            &cancellation,
+        )
+        .unwrap()
+        .probability;
+        let integral = moivre_laplace_integral(
+            1000,
+            480,
+            520,
+            ratio(1, 2),
+            settings(&cancellation),
+        )
+        .unwrap()
+        .probability;
+        let corrected = continuity_corrected_interval(
+            1000,
+            480,
+            520,
+            ratio(1, 2),
+            settings(&cancellation),
+        )
+        .unwrap()
+        .probability;
+
+        assert!(
+            relative_error(&corrected, &exact)
+                < relative_error(&integral, &exact)
+        );
+        assert!(
+            relative_error(&corrected, &exact)
+                < decimal("0.001")
+        );
+    }
+
+    #[test]
+    fn no_iterations_are_rejected() {
+        let cancellation = Cancellation::new();
+        let settings = Settings {
+            iterations: Iterations::Fixed(0),
+            ..settings(&cancellation)
+        };
+        assert_eq!(
+            moivre_laplace(100, 50, ratio(1, 2), settings)
+                .err(),
+            Some(SolverError::NoIterations)
+        );
+        assert_eq!(
+            poisson_interval(
+                100,
+                0,
+                5,
+                ratio(1, 20),
+                settings
+            )
+            .err(),
+            Some(SolverError::NoIterations)
+        );
+    }
+
+    #[test]
+    fn ln_encloses_above_and_below_one() {
+        // ln(2) = 0.69314718055994530941...
+        let cancellation = Cancellation::new();
+        let (lower, upper) =
+            ln(2u32.into(), 1u32.into(), 20, &cancellation)
+                .unwrap();
+        assert!(lower <= decimal("0.69314718055994530942"));
+        assert!(decimal("0.69314718055994530941") <= upper);
+        assert!(
+            upper - lower
+                < decimal("0.0000000000000000001")
+        );
+
+        let (lower, upper) =
+            ln(1u32.into(), 2u32.into(), 20, &cancellation)
+                .unwrap();
+        assert!(
+            lower <= -decimal("0.69314718055994530941")
+        );
+        assert!(
+            -decimal("0.69314718055994530942") <= upper
+        );
+    }
+
+    #[test]
+    fn reduced_exp_encloses_e() {
+        // e = 2.71828182845904523536028...
+        let Exp {
+            numer,
+            denom,
+            upper,
+            ..
+        } = Iterations::Reduced(25)
+            .exp(
+                1u32.into(),
+                1u32.into(),
+                &|_| {},
+                &Cancellation::new(),
+            )
+            .unwrap();
+        let (upper_numer, upper_denom) = upper.unwrap();
+        let lower = GenericFraction::new(numer, denom);
+        let upper =
+            GenericFraction::new(upper_numer, upper_denom);
+        assert!(
+            lower <= decimal("2.71828182845904523536029")
+        );
+        assert!(
+            decimal("2.71828182845904523536028") <= upper
+        );
+        assert!(
+            upper - lower
+                < decimal("0.0000000000000000000000001")
+        );
+    }
+
+    #[test]
+    fn sqrt_to_digits_is_an_upper_bound_within_the_tolerance(
+    ) {
+        let (root, scale) = sqrt_to_digits(
+            2u32.into(),
+            1u32.into(),
+            30,
+            &|_| {},
+            &Cancellation::new(),
+        )
+        .unwrap();
+        // 10^-30 is 10 steps of the scale 10^31
+        let target = 2u32 * &scale * &scale;
+        assert!(&root * &root >= target);
+        let below = &root - 10u32;
+        assert!(&below * &below < target);
+    }
+
+    #[test]
+    fn factorial_is_the_product_of_its_range() {
+        assert_eq!(
+            factorial_new(20u32.into()),
+            BigUint::from(2432902008176640000u64)
+        );
+        assert_eq!(
+            factorial_new(1000u32.into()),
+            (1..=1000u32)
+                .map(BigUint::from)
+                .product::<BigUint>()
+        );
+        assert_eq!(
+            factorial_new(0u32.into()),
+            BigUint::from(1u32)
+        );
+    }
+}
@@ -1,22 +1,479 @@
 use fraction::GenericFraction;
 use num_bigint::BigUint;
 use num_rational::Ratio;
-use solver::{bernoulli, moivre_laplace};
-use time::Instant;
+use serde_json::json;
+use solver::{
+    leading_digits, Cancellation, Iterations, Method,
+    Query, Settings, SolverError, SolverResult, SquareRoot,
+};
 
-#[cfg(target_family = "wasm")]
-pub fn log<T: Into<JsValue>>(value: T) {
-    web_sys::console::log_1(&value.into());
+mod batch;
+mod sweep_cli;
+
+const USAGE: &str = "\
+Usage: solver <method> -n <experiments> -k <outcomes> -p <probability> [options]
+       solver batch <file> [--format csv|jsonl] [--precision <D>]
+       solver sweep -n <range> -k <range|mean> -p <range> [options]
+       solver heatmap -n <range> -p <range> [--offset <D>] [options]
+
+Methods:
+    bernoulli           Exact binomial probability
+    moivre-laplace      Local or integral Moivre-Laplace theorem
+    continuity-corrected
+                        Normal approximation with the continuity
+                        correction, Φ(k + 1/2) - Φ(k - 1/2)
+    edgeworth           Local theorem with Edgeworth terms up to --order,
+                        only for a single k
+    stirling            Stirling series for the factorials with --terms
+                        corrections and guaranteed bounds, only for a
+                        single k
+    saddle-point        Saddle point approximation, Lugannani-Rice for
+                        intervals
+    poisson             Poisson approximation
+    batch               Every method for each scenario of a CSV or
+                        JSONL file, see --help of batch
+    sweep               Moivre-Laplace error over ranges of n, k and p,
+                        see --help of sweep
+    heatmap             Moivre-Laplace relative error on a grid of n and
+                        p, see --help of heatmap
+
+Options:
+    -n <N>                  Total amount of experiments
+    -k <K>                  Required amount of positive outcomes
+    --to <K2>               Computes P(K <= X <= K2) instead of P(X = K)
+    -p <P>                  Probability of a positive outcome, either a
+                            fraction like 8/10 or a decimal like 0.8
+    --iterations <N>        Terms of the exp series [default: 300]
+    --until-stable <D>      Sum the series until D digits stop changing
+    --reduce                Range reduced exp, good to --precision digits
+    --sqrt-iterations <N>   Newton steps of the square root [default: 10]
+    --sqrt-digits <D>       Newton steps until D digits are correct
+    --precision <D>         Digits of the output and of pi [default: 50]
+    --order <O>             Order of edgeworth, 0 to 2 [default: 2]
+    --terms <T>             Corrections of stirling, 0 to 9 [default: 3]
+    --format <F>            fraction, decimal, scientific or json
+                            [default: decimal]
+    -h, --help              Print this message";
+
+#[derive(Clone, Copy)]
+enum Format {
+    Fraction,
+    Decimal,
+    Scientific,
+    Json,
+}
+
+struct Options {
+    method: Method,
+    experiments: u32,
+    outcomes: u32,
+    to: Option<u32>,
+    probability: Ratio<BigUint>,
+    iterations: usize,
+    until_stable: Option<usize>,
+    reduce: bool,
+    sqrt_iterations: usize,
+    sqrt_digits: Option<usize>,
+    precision: usize,
+    order: u32,
+    terms: usize,
+    format: Format,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            method: Method::Bernoulli,
+            experiments: 0,
+            outcomes: 0,
+            to: None,
+            probability: Ratio::new_raw(
+                0u32.into(),
+                1u32.into(),
+            ),
+            iterations: 300,
+            until_stable: None,
+            reduce: false,
+            sqrt_iterations: 10,
+            sqrt_digits: None,
+            precision: 50,
+            order: 2,
+            terms: 3,
+            format: Format::Decimal,
+        }
+    }
+}
+
+impl Options {
+    // Same priority as the toggles in the web app
+    fn exponent_iterations(&self) -> Iterations {
+        if self.reduce {
+            Iterations::Reduced(self.precision)
+        } else if let Some(digits) = self.until_stable {
+            Iterations::UntilStable(digits)
+        } else {
+            Iterations::Fixed(self.iterations)
+        }
+    }
+
+    fn square_root(&self) -> SquareRoot {
+        match self.sqrt_digits {
+            Some(digits) => SquareRoot::Digits(digits),
+            None => {
+                SquareRoot::Iterations(self.sqrt_iterations)
+            }
+        }
+    }
+
+    fn solve(&self) -> Result<SolverResult, SolverError> {
+        let cancellation = Cancellation::new();
+        self.method.solve(
+            Query {
+                experiments: self.experiments,
+                outcomes: self.outcomes,
+                to: self.to,
+                probability: self.probability.clone(),
+                edgeworth_order: self.order,
+                stirling_terms: self.terms,
+            },
+            Settings {
+                iterations: self.exponent_iterations(),
+                square_root: self.square_root(),
+                pi_digits: self.precision,
+                digits: self.precision,
+                progress: &|_| {},
+                cancellation: &cancellation,
+            },
+        )
+    }
+}
+
+fn parse_method(method: &str) -> Result<Method, String> {
+    match method {
+        "bernoulli" => Ok(Method::Bernoulli),
+        "moivre-laplace" => Ok(Method::MoivreLaplace),
+        "continuity-corrected" => {
+            Ok(Method::ContinuityCorrected)
+        }
+        "edgeworth" => Ok(Method::Edgeworth),
+        "stirling" => Ok(Method::Stirling),
+        "saddle-point" => Ok(Method::SaddlePoint),
+        "poisson" => Ok(Method::Poisson),
+        _ => Err(format!("Unknown method {}", method)),
+    }
+}
+
+fn parse_format(format: &str) -> Result<Format, String> {
+    match format {
+        "fraction" => Ok(Format::Fraction),
+        "decimal" => Ok(Format::Decimal),
+        "scientific" => Ok(Format::Scientific),
+        "json" => Ok(Format::Json),
+        _ => Err(format!("Unknown format {}", format)),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(
+    flag: &str,
+    value: &str,
+) -> Result<T, String> {
+    value.parse().map_err(|_| {
+        format!(
+            "{} expects a whole number, got {}",
+            flag, value
+        )
+    })
+}
+
+// Accepts numer/denom or a decimal like 0.25, which is kept
+// exact as 25/100
+fn parse_probability(
+    value: &str,
+) -> Result<Ratio<BigUint>, String> {
+    let invalid =
+        || format!("Invalid probability {}", value);
+
+    if let Some((numer, denom)) = value.split_once('/') {
+        let numer =
+            numer.trim().parse().map_err(|_| invalid())?;
+        let denom =
+            denom.trim().parse().map_err(|_| invalid())?;
+        return Ok(Ratio::new_raw(numer, denom));
+    }
+
+    let (whole, decimals) =
+        value.split_once('.').unwrap_or((value, ""));
+    if whole.is_empty() && decimals.is_empty() {
+        return Err(invalid());
+    }
+    let digits = format!("{}{}", whole, decimals);
+    if !digits.chars().all(|ch| ch.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    Ok(Ratio::new_raw(
+        digits.parse().map_err(|_| invalid())?,
+        BigUint::from(10u32).pow(decimals.len() as u32),
+    ))
 }
 
-use std::fmt::Display;
-#[cfg(not(target_family = "wasm"))]
-pub fn log<T: Display>(value: T) {
-    println!("{}", value);
+// Options of the series and the square root, which the sweep
+// takes as well. Returns false when the flag isn't one of them
+fn parse_series_option(
+    options: &mut Options,
+    flag: &str,
+    value: &str,
+) -> Result<bool, String> {
+    match flag {
+        "--iterations" => {
+            options.iterations = parse_number(flag, value)?
+        }
+        "--until-stable" => {
+            options.until_stable =
+                Some(parse_number(flag, value)?)
+        }
+        "--sqrt-iterations" => {
+            options.sqrt_iterations =
+                parse_number(flag, value)?
+        }
+        "--sqrt-digits" => {
+            options.sqrt_digits =
+                Some(parse_number(flag, value)?)
+        }
+        "--precision" => {
+            options.precision = parse_number(flag, value)?
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+// Returns None when help was asked for
+fn parse(
+    mut args: impl Iterator<Item = String>,
+) -> Result<Option<Options>, String> {
+    let mut method = None;
+    let mut experiments = None;
+    let mut outcomes = None;
+    let mut probability = None;
+    let mut options = Options::default();
+
+    while let Some(arg) = args.next() {
+        // Both --flag value and --flag=value are accepted
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value))
+                if arg.starts_with("--") =>
+            {
+                (flag.to_string(), Some(value.to_string()))
+            }
+            _ => (arg, None),
+        };
+
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--reduce" => {
+                options.reduce = true;
+                continue;
+            }
+            _ if !flag.starts_with('-') => {
+                if method.is_some() {
+                    return Err(format!(
+                        "Unexpected argument {}",
+                        flag
+                    ));
+                }
+                method = Some(parse_method(&flag)?);
+                continue;
+            }
+            _ => {}
+        }
+
+        let value = match inline {
+            Some(value) => value,
+            None => args.next().ok_or_else(|| {
+                format!("{} expects a value", flag)
+            })?,
+        };
+
+        match flag.as_str() {
+            "-n" => {
+                experiments =
+                    Some(parse_number(&flag, &value)?)
+            }
+            "-k" => {
+                outcomes =
+                    Some(parse_number(&flag, &value)?)
+            }
+            "--to" => {
+                options.to =
+                    Some(parse_number(&flag, &value)?)
+            }
+            "-p" => {
+                probability =
+                    Some(parse_probability(&value)?)
+            }
+            "--format" => {
+                options.format = parse_format(&value)?
+            }
+            "--order" => {
+                options.order = parse_number(&flag, &value)?
+            }
+            "--terms" => {
+                options.terms = parse_number(&flag, &value)?
+            }
+            _ => {
+                if !parse_series_option(
+                    &mut options,
+                    &flag,
+                    &value,
+                )? {
+                    return Err(format!(
+                        "Unknown option {}",
+                        flag
+                    ));
+                }
+            }
+        }
+    }
+
+    options.method = method.ok_or("Missing method")?;
+    options.experiments =
+        experiments.ok_or("Missing -n")?;
+    options.outcomes = outcomes.ok_or("Missing -k")?;
+    options.probability =
+        probability.ok_or("Missing -p")?;
+
+    Ok(Some(options))
+}
+
+// d.ddd...e-x with `digits` significant digits, truncated
+fn scientific(
+    probability: &GenericFraction<BigUint>,
+    digits: usize,
+) -> String {
+    let GenericFraction::Rational(_, ratio) = probability
+    else {
+        return probability.to_string();
+    };
+    if ratio.numer() == &BigUint::from(0u32) {
+        return "0".into();
+    }
+
+    let digits = digits.max(1);
+    let (mantissa, shift) = leading_digits(
+        ratio.numer(),
+        ratio.denom(),
+        digits,
+    );
+    let mantissa = mantissa.to_string();
+    let exponent = digits as i64 - 1 - shift;
+
+    match mantissa.split_at(1) {
+        (first, "") => format!("{}e{}", first, exponent),
+        (first, rest) => {
+            format!("{}.{}e{}", first, rest, exponent)
+        }
+    }
+}
+
+fn reduced(
+    probability: &GenericFraction<BigUint>,
+) -> (BigUint, BigUint) {
+    match probability {
+        GenericFraction::Rational(_, ratio) => {
+            ratio.reduced().into()
+        }
+        _ => (0u32.into(), 1u32.into()),
+    }
+}
+
+fn print(options: &Options, result: &SolverResult) {
+    let precision = options.precision;
+    let probability = &result.probability;
+    let accurate_digits = result
+        .bounds
+        .as_ref()
+        .map(|bounds| bounds.accurate_digits());
+
+    match options.format {
+        Format::Fraction => {
+            let (numer, denom) = reduced(probability);
+            println!("{}/{}", numer, denom)
+        }
+        Format::Decimal => {
+            println!("{:.precision$}", probability)
+        }
+        Format::Scientific => {
+            println!(
+                "{}",
+                scientific(probability, precision)
+            )
+        }
+        Format::Json => {
+            let (numer, denom) = reduced(probability);
+            let output = json!({
+                "numer": numer.to_string(),
+                "denom": denom.to_string(),
+                "decimal": format!("{:.precision$}", probability),
+                "scientific": scientific(probability, precision),
+                "took_ns": result.took.whole_nanoseconds() as u64,
+                "iterations": result.iterations,
+                // null when there are no bounds, "exact" when they meet
+                "accurate_digits": match accurate_digits {
+                    Some(Some(digits)) => json!(digits),
+                    Some(None) => json!("exact"),
+                    None => json!(null),
+                },
+            });
+            println!("{}", output);
+            return;
+        }
+    }
+
+    println!("took: {}", result.took);
+    println!("iterations: {}", result.iterations);
+    match accurate_digits {
+        Some(Some(digits)) => {
+            println!("accurate to {} digits", digits)
+        }
+        Some(None) => println!("exact"),
+        None => {}
+    }
 }
 
 fn main() {
-    println!("{:.50}", bernoulli(100, 80, Ratio::new_raw(8u32.into(), 10u32.into())).probability);
-    println!("{:.50}", moivre_laplace(100, 80, Ratio::new_raw(8u32.into(), 10u32.into()), 500, 10).probability);
-    
+    let mut args = std::env::args().skip(1).peekable();
+    let result = match args.peek().map(String::as_str) {
+        Some("batch") => batch::run(args.skip(1)),
+        Some("sweep") => sweep_cli::run(args.skip(1)),
+        Some("heatmap") => sweep_cli::heatmap(args.skip(1)),
+        _ => return solve(args),
+    };
+
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        std::process::exit(2);
+    }
+}
+
+fn solve(args: impl Iterator<Item = String>) {
+    let options = match parse(args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    match options.solve() {
+        Ok(result) => print(&options, &result),
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    }
 }
@@ -0,0 +1,163 @@
+use num_bigint::BigUint;
+use num_rational::Ratio;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bernoulli, bernoulli_interval, continuity_corrected,
+    continuity_corrected_interval, edgeworth,
+    moivre_laplace, moivre_laplace_integral, poisson,
+    poisson_interval, saddle_point, saddle_point_interval,
+    stirling, Settings, SolverError, SolverResult,
+};
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum Method {
+    Bernoulli,
+    MoivreLaplace,
+    ContinuityCorrected,
+    Edgeworth,
+    Stirling,
+    SaddlePoint,
+    Poisson,
+}
+
+// P(X = outcomes) for n experiments with probability p, or
+// P(outcomes <= X <= to) when to is set
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Query {
+    pub experiments: u32,
+    pub outcomes: u32,
+    pub to: Option<u32>,
+    pub probability: Ratio<BigUint>,
+    // Order of the Edgeworth correction, see edgeworth
+    pub edgeworth_order: u32,
+    // Corrections of the Stirling series, see stirling
+    pub stirling_terms: usize,
+}
+
+impl Method {
+    // Runs the method on the query. Stirling computes to
+    // pi_digits digits, the same as the precision of pi
+    pub fn solve(
+        self,
+        query: Query,
+        settings: Settings,
+    ) -> Result<SolverResult, SolverError> {
+        let Query {
+            experiments,
+            outcomes,
+            to,
+            probability,
+            edgeworth_order,
+            stirling_terms,
+        } = query;
+
+        match (self, to) {
+            (Method::Bernoulli, Some(to)) => {
+                bernoulli_interval(
+                    experiments,
+                    outcomes,
+                    to,
+                    probability,
+                    settings.cancellation,
+                )
+            }
+            (Method::Bernoulli, None) => bernoulli(
+                experiments,
+                outcomes,
+                probability,
+                settings.cancellation,
+            ),
+            (Method::MoivreLaplace, Some(to)) => {
+                moivre_laplace_integral(
+                    experiments,
+                    outcomes,
+                    to,
+                    probability,
+                    settings,
+                )
+            }
+            (Method::MoivreLaplace, None) => {
+                moivre_laplace(
+                    experiments,
+                    outcomes,
+                    probability,
+                    settings,
+                )
+            }
+            (Method::ContinuityCorrected, Some(to)) => {
+                continuity_corrected_interval(
+                    experiments,
+                    outcomes,
+                    to,
+                    probability,
+                    settings,
+                )
+            }
+            (Method::ContinuityCorrected, None) => {
+                continuity_corrected(
+                    experiments,
+                    outcomes,
+                    probability,
+                    settings,
+                )
+            }
+            (Method::Edgeworth, Some(_))
+            | (Method::Stirling, Some(_)) => {
+                Err(SolverError::IntervalUnsupported)
+            }
+            (Method::Edgeworth, None) => edgeworth(
+                experiments,
+                outcomes,
+                probability,
+                edgeworth_order,
+                settings,
+            ),
+            (Method::Stirling, None) => stirling(
+                experiments,
+                outcomes,
+                probability,
+                stirling_terms,
+                settings,
+            ),
+            (Method::SaddlePoint, Some(to)) => {
+                saddle_point_interval(
+                    experiments,
+                    outcomes,
+                    to,
+                    probability,
+                    settings,
+                )
+            }
+            (Method::SaddlePoint, None) => saddle_point(
+                experiments,
+                outcomes,
+                probability,
+                settings,
+            ),
+            (Method::Poisson, Some(to)) => {
+                poisson_interval(
+                    experiments,
+                    outcomes,
+                    to,
+                    probability,
+                    settings,
+                )
+            }
+            (Method::Poisson, None) => poisson(
+                experiments,
+                outcomes,
+                probability,
+                settings,
+            ),
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::cell::RefCell;
+
+use num_bigint::BigUint;
+
+// Guard digits on top of the requested ones, they absorb the rounding errors of the fixed point
+// arithmetic below
+const GUARD_DIGITS: usize = 10;
+
+// Pi in fixed point with a known error, the real value lies within
+// (numer - error) / denom ..= (numer + error) / denom
+#[derive(Clone)]
+pub struct Pi {
+    pub numer: BigUint,
+    pub error: BigUint,
+    pub denom: BigUint,
+    pub digits: usize,
+}
+
+impl Pi {
+    pub fn lower(&self) -> (BigUint, BigUint) {
+        (&self.numer - &self.error, self.denom.clone())
+    }
+
+    pub fn upper(&self) -> (BigUint, BigUint) {
+        (&self.numer + &self.error, self.denom.clone())
+    }
+}
+
+thread_local! {
+    // Workers stay alive between requests, so the most precise pi computed so far is kept around
+    static CACHE: RefCell<Option<Pi>> =
+        const { RefCell::new(None) };
+}
+
+// Pi good to at least `digits` numbers after the dot. Results are cached, so asking for fewer
+// digits than before returns the previous, more precise, value
+pub fn pi(digits: usize) -> Pi {
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        match cache.as_ref() {
+            Some(cached) if cached.digits >= digits => {
+                cached.clone()
+            }
+            _ => {
+                let pi = machin(digits);
+                *cache = Some(pi.clone());
+                pi
+            }
+        }
+    })
+}
+
+// Machin's formula, pi = 16 * arctan(1/5) - 4 * arctan(1/239)
+fn machin(digits: usize) -> Pi {
+    let denom = BigUint::from(10u32)
+        .pow((digits + GUARD_DIGITS) as u32);
+
+    let (fifth, fifth_terms) = arctan_inverse(5, &denom);
+    let (inverse, inverse_terms) =
+        arctan_inverse(239, &denom);
+
+    // Every term of arctan is off by less than one after flooring, and the first dropped term
+    // is below one as well
+    let error = BigUint::from(
+        16 * (fifth_terms + 1) + 4 * (inverse_terms + 1),
+    );
+
+    Pi {
+        numer: 16u32 * fifth - 4u32 * inverse,
+        error,
+        denom,
+        digits,
+    }
+}
+
+// arctan(1/x) * denom = sum((-1)^k * denom / (x^(2k+1) * (2k+1))) in fixed point. Returns the
+// floored sum along with the amount of terms in it. Flooring is nested, so each power is exactly
+// floor(denom / x^(2k+1))
+fn arctan_inverse(
+    x: u32,
+    denom: &BigUint,
+) -> (BigUint, usize) {
+    let x_sq = x * x;
+
+    let mut power = denom / x;
+    let (mut positive, mut negative) =
+        (BigUint::from(0u32), BigUint::from(0u32));
+    let mut terms = 0;
+
+    while power > BigUint::from(0u32) {
+        let term = &power / (2 * terms as u32 + 1);
+        if terms % 2 == 0 {
+            positive += term;
+        } else {
+            negative += term;
+        }
+        power /= x_sq;
+        terms += 1;
+    }
+
+    (positive - negative, terms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pi_encloses_its_digits() {
+        // 3.14159265358979323846264338327950288419716...
+        let pi = machin(40);
+        let digits = BigUint::from(10u32).pow(40);
+        let below: BigUint =
+            "31415926535897932384626433832795028841971"
+                .parse()
+                .unwrap();
+        let above = &below + 1u32;
+        let (lower, lower_denom) = pi.lower();
+        let (upper, upper_denom) = pi.upper();
+        assert!(lower * &digits <= &above * lower_denom);
+        assert!(&below * upper_denom <= upper * &digits);
+        assert!(pi.error * &digits <= pi.denom);
+    }
+}
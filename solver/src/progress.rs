@@ -0,0 +1,78 @@
+use fraction::GenericFraction;
+use num_bigint::BigUint;
+use num_rational::Ratio;
+use serde::{Deserialize, Serialize};
+
+// Intermediate state of exp or sqrt, handed to a callback
+// while they are running
+pub struct Progress<'a> {
+    pub iteration: usize,
+    // Amount of iterations that will be done, if it is known
+    // up front
+    pub total: Option<usize>,
+    // Current partial sum or Newton iterate as numer / denom,
+    // None when it isn't an approximation of the final value
+    pub value: Option<(&'a BigUint, &'a BigUint)>,
+}
+
+// Which part of a solver is running
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Stage {
+    SquareRoot,
+    Exp,
+    LaplaceSeries,
+    // Points of a sweep, see sweep::sweep
+    Sweep,
+    // Outcomes of solver::distribution
+    Distribution,
+}
+
+impl std::fmt::Display for Stage {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            Stage::SquareRoot => write!(f, "Square root"),
+            Stage::Exp => write!(f, "Exponent"),
+            Stage::LaplaceSeries => {
+                write!(f, "Laplace series")
+            }
+            Stage::Sweep => write!(f, "Sweep"),
+            Stage::Distribution => {
+                write!(f, "Distribution")
+            }
+        }
+    }
+}
+
+// Snapshot of a solver that is still running. The
+// probability is computed from the partial value of the
+// current stage, when every other part of it is known
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Partial {
+    pub stage: Stage,
+    pub iteration: u32,
+    pub total: Option<u32>,
+    pub probability: Option<GenericFraction<BigUint>>,
+}
+
+impl Partial {
+    pub fn new(
+        stage: Stage,
+        progress: &Progress,
+        probability: Option<Ratio<BigUint>>,
+    ) -> Self {
+        Self {
+            stage,
+            iteration: progress.iteration as u32,
+            total: progress.total.map(|total| total as u32),
+            probability: probability.map(|probability| {
+                GenericFraction::Rational(
+                    fraction::Sign::Plus,
+                    probability,
+                )
+            }),
+        }
+    }
+}
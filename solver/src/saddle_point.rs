@@ -0,0 +1,501 @@
+use fraction::{GenericFraction, Zero};
+use num_bigint::BigUint;
+use num_rational::Ratio;
+use web_time::Instant;
+
+use crate::{
+    ln, pi, took, validate_iterations, validate_outcomes,
+    validate_probability, Exp, Partial, Settings,
+    SolverError, SolverResult, Stage,
+};
+
+type F = GenericFraction<BigUint>;
+
+// Everything about n and p the saddle point formulas need
+struct Binomial<'a> {
+    experiments: u32,
+    positive_numer: BigUint,
+    negative_numer: BigUint,
+    prob_denom: BigUint,
+    settings: Settings<'a>,
+}
+
+// Saddle point approximation of P(X = k). The cumulant generating
+// function K(s) = n ln(q + p e^s) has its saddle point K'(s) = k at
+// e^s = kq / ((n - k) p), where e^(K(s) - sk) / sqrt(2π K''(s))
+// comes down to (np / k)^k (nq / (n - k))^(n - k)
+// * sqrt(n / (2π k (n - k))). For k = 0 and k = n there is no saddle
+// point, but q^n and p^n are exact there anyway
+pub fn saddle_point(
+    experiments: u32,
+    positive_outcomes: u32,
+    positive_probability: Ratio<BigUint>,
+    settings: Settings,
+) -> Result<SolverResult, SolverError> {
+    let now = Instant::now();
+
+    validate_outcomes(experiments, positive_outcomes)?;
+    validate_probability(&positive_probability)?;
+    validate_iterations(settings.iterations)?;
+
+    let binomial = Binomial::new(
+        experiments,
+        positive_probability,
+        settings,
+    );
+    let (probability, iterations) =
+        binomial.point(positive_outcomes)?;
+
+    let elapsed = now.elapsed();
+
+    Ok(SolverResult {
+        probability,
+        took: took(elapsed)?,
+        iterations: iterations as u32,
+        bounds: None,
+    })
+}
+
+// P(from <= X <= to) = P(X >= from) - P(X >= to + 1), with both
+// tails from the Lugannani-Rice formula, see Binomial::tail
+pub fn saddle_point_interval(
+    experiments: u32,
+    from: u32,
+    to: u32,
+    positive_probability: Ratio<BigUint>,
+    settings: Settings,
+) -> Result<SolverResult, SolverError> {
+    let now = Instant::now();
+
+    validate_probability(&positive_probability)?;
+    validate_iterations(settings.iterations)?;
+
+    let to = to.min(experiments);
+
+    let binomial = Binomial::new(
+        experiments,
+        positive_probability,
+        settings,
+    );
+    let (probability, iterations) = if from > to {
+        (F::zero(), 0)
+    } else {
+        let (lower, lower_iterations) =
+            binomial.tail(from)?;
+        let (upper, upper_iterations) =
+            binomial.tail(to + 1)?;
+        (
+            lower - upper,
+            lower_iterations.max(upper_iterations),
+        )
+    };
+
+    let elapsed = now.elapsed();
+
+    Ok(SolverResult {
+        probability,
+        took: took(elapsed)?,
+        iterations: iterations as u32,
+        bounds: None,
+    })
+}
+
+impl<'a> Binomial<'a> {
+    fn new(
+        experiments: u32,
+        positive_probability: Ratio<BigUint>,
+        settings: Settings<'a>,
+    ) -> Self {
+        let (positive_numer, prob_denom) =
+            positive_probability.into();
+        Self {
+            experiments,
+            negative_numer: &prob_denom - &positive_numer,
+            positive_numer,
+            prob_denom,
+            settings,
+        }
+    }
+
+    // p^k q^(n-k), only used at the ends where C(n, k) = 1
+    fn exact_end(&self, outcomes: u32) -> F {
+        F::new(
+            self.positive_numer.pow(outcomes)
+                * self
+                    .negative_numer
+                    .pow(self.experiments - outcomes),
+            self.prob_denom.pow(self.experiments),
+        )
+    }
+
+    // Whether the saddle point is undefined, p = 0 or 1 puts all of
+    // the probability on one end
+    fn degenerate(&self, outcomes: u32) -> bool {
+        outcomes == 0
+            || outcomes == self.experiments
+            || self.positive_numer.is_zero()
+            || self.negative_numer.is_zero()
+    }
+
+    // Digits of the logarithms and of every intermediate value. k and
+    // n - k multiply the logarithms, which costs their digits of
+    // absolute precision
+    fn digits(&self) -> usize {
+        self.settings.pi_digits
+            + self.experiments.to_string().len()
+            + 2
+    }
+
+    // numer / denom rounded down to `digits` significant digits. Fixed
+    // Newton steps and long series leave numbers with hundreds of
+    // thousands of bits, which the fraction arithmetic below would
+    // otherwise reduce with a gcd after every operation. The
+    // precision is relative, so that tiny tail probabilities don't
+    // round down to 0
+    fn rounded(
+        &self,
+        numer: &BigUint,
+        denom: &BigUint,
+    ) -> F {
+        if numer.is_zero() {
+            return F::zero();
+        }
+        // log2(10) ~ 3.33 bits for every decimal digit
+        let bits = self.digits() as i64 * 333 / 100 + 16;
+        let shift = bits - numer.bits() as i64
+            + denom.bits() as i64;
+        if shift >= 0 {
+            F::new(
+                (numer << shift as u64) / denom,
+                BigUint::from(1u32) << shift as u64,
+            )
+        } else {
+            F::new(
+                (numer / (denom << -shift as u64))
+                    << -shift as u64,
+                1u32,
+            )
+        }
+    }
+
+    fn sqrt(
+        &self,
+        numer: BigUint,
+        denom: BigUint,
+    ) -> Result<F, SolverError> {
+        let (numer, denom) =
+            self.settings.square_root.sqrt(
+                numer,
+                denom,
+                &|step| {
+                    (self.settings.progress)(Partial::new(
+                        Stage::SquareRoot,
+                        &step,
+                        None,
+                    ))
+                },
+                self.settings.cancellation,
+            )?;
+        Ok(self.rounded(&numer, &denom))
+    }
+
+    // k ln(np / k) + (n - k) ln(nq / (n - k)) = -n KL(k/n || p),
+    // which is never positive. Returned negated, as the midpoint of
+    // the enclosure of ln
+    fn divergence(
+        &self,
+        outcomes: u32,
+    ) -> Result<F, SolverError> {
+        let rest = self.experiments - outcomes;
+        let digits = self.digits();
+        let (first_lower, first_upper) = ln(
+            outcomes * &self.prob_denom,
+            self.experiments * &self.positive_numer,
+            digits,
+            self.settings.cancellation,
+        )?;
+        let (second_lower, second_upper) = ln(
+            rest * &self.prob_denom,
+            self.experiments * &self.negative_numer,
+            digits,
+            self.settings.cancellation,
+        )?;
+
+        let divergence = (F::from(outcomes)
+            * (first_lower + first_upper)
+            + F::from(rest)
+                * (second_lower + second_upper))
+            / F::from(2u32);
+        // Rounding can only push it below zero when k = np
+        Ok(if divergence < F::zero() {
+            F::zero()
+        } else {
+            divergence
+        })
+    }
+
+    // e^-x for x >= 0
+    fn exp_negative(
+        &self,
+        exponent: &F,
+    ) -> Result<(F, usize), SolverError> {
+        let GenericFraction::Rational(_, exponent) =
+            exponent
+        else {
+            unreachable!("the exponent is a finite sum")
+        };
+        let Exp {
+            numer,
+            denom,
+            iterations,
+            ..
+        } = self.settings.iterations.exp(
+            exponent.numer().clone(),
+            exponent.denom().clone(),
+            &|step| {
+                (self.settings.progress)(Partial::new(
+                    Stage::Exp,
+                    &step,
+                    None,
+                ))
+            },
+            self.settings.cancellation,
+        )?;
+        Ok((self.rounded(&denom, &numer), iterations))
+    }
+
+    fn point(
+        &self,
+        outcomes: u32,
+    ) -> Result<(F, usize), SolverError> {
+        if self.degenerate(outcomes) {
+            return Ok((self.exact_end(outcomes), 0));
+        }
+
+        let rest = self.experiments - outcomes;
+        let pi = pi(self.settings.pi_digits);
+        let root = self.sqrt(
+            self.experiments * pi.denom,
+            2u32 * BigUint::from(outcomes)
+                * BigUint::from(rest)
+                * pi.numer,
+        )?;
+        let (exp, iterations) =
+            self.exp_negative(&self.divergence(outcomes)?)?;
+
+        Ok((root * exp, iterations))
+    }
+
+    // Lugannani-Rice with the continuity correction of Daniels for
+    // lattice variables, P(X >= k) ~ 1 - N(w) + φ(w) (1/u - 1/w)
+    // with w = sign(k - np) sqrt(2 n KL(k/n || p)) and
+    // u = (1 - e^-s) sqrt(K''(s)) at the saddle point s of k, where
+    // e^-s = (n - k) p / (kq) and K''(s) = k (n - k) / n
+    fn tail(
+        &self,
+        outcomes: u32,
+    ) -> Result<(F, usize), SolverError> {
+        let experiments = self.experiments;
+        if outcomes == 0 {
+            return Ok((F::from(1u32), 0));
+        }
+        if outcomes > experiments {
+            return Ok((F::zero(), 0));
+        }
+        if self.degenerate(outcomes) {
+            // p = 0 or 1 leaves a single outcome with all of the
+            // probability, and X >= n is X = n
+            let all = if self.negative_numer.is_zero()
+                || outcomes == experiments
+            {
+                self.exact_end(experiments)
+            } else {
+                F::zero()
+            };
+            return Ok((all, 0));
+        }
+
+        let half = F::new(1u32, 2u32);
+        let pi = pi(self.settings.pi_digits);
+
+        let rest = experiments - outcomes;
+        let scaled_outcomes =
+            BigUint::from(outcomes) * &self.prob_denom;
+        let np = experiments * &self.positive_numer;
+
+        // At k = np both w and u are 0, where the formula tends to
+        // 1/2 - κ3 / (6 sqrt(2π) κ2^(3/2)) + 1 / (2 sqrt(2π κ2)) with
+        // κ2 = npq and κ3 = npq (q - p), which is
+        // 1/2 + (1 + p) / (3 sqrt(2π npq))
+        if scaled_outcomes == np {
+            let npq = F::new(
+                &np * &self.negative_numer,
+                &self.prob_denom * &self.prob_denom,
+            );
+            let GenericFraction::Rational(_, npq) = npq
+            else {
+                unreachable!("npq is positive here")
+            };
+            let root = self.sqrt(
+                2u32 * npq.numer() * &pi.numer,
+                npq.denom() * &pi.denom,
+            )?;
+            let p = F::new(
+                self.positive_numer.clone(),
+                self.prob_denom.clone(),
+            );
+            return Ok((
+                half + (F::from(1u32) + p)
+                    / (F::from(3u32) * root),
+                0,
+            ));
+        }
+
+        let sign = if scaled_outcomes > np {
+            fraction::Sign::Plus
+        } else {
+            fraction::Sign::Minus
+        };
+        let signed = |value: F| match sign {
+            fraction::Sign::Plus => value,
+            fraction::Sign::Minus => -value,
+        };
+
+        let sqrt_two_pi =
+            self.sqrt(2u32 * pi.numer, pi.denom)?;
+
+        // w^2 / 2 = n KL(k/n || p)
+        let divergence = self.divergence(outcomes)?;
+        let GenericFraction::Rational(_, w_sq) =
+            divergence.clone() * F::from(2u32)
+        else {
+            unreachable!("the divergence is finite")
+        };
+        let w = self.sqrt(
+            w_sq.numer().clone(),
+            w_sq.denom().clone(),
+        )?;
+
+        let (exp, exp_iterations) =
+            self.exp_negative(&divergence)?;
+        let density = exp.clone() / sqrt_two_pi.clone();
+
+        // N(w) - 1/2 = w / sqrt(2π) e^(-w^2/2) * series(w^2)
+        let (series_numer, series_denom, series_iterations) =
+            self.settings.iterations.laplace_series(
+                w_sq.numer().clone(),
+                w_sq.denom().clone(),
+                &|step| {
+                    (self.settings.progress)(Partial::new(
+                        Stage::LaplaceSeries,
+                        &step,
+                        None,
+                    ))
+                },
+                self.settings.cancellation,
+            )?;
+        let laplace = w.clone()
+            * density.clone()
+            * self.rounded(&series_numer, &series_denom);
+
+        // 1 - e^-s = (kq - (n - k) p) / kq, which has the sign of
+        // k - np
+        let kq =
+            BigUint::from(outcomes) * &self.negative_numer;
+        let rest_p =
+            BigUint::from(rest) * &self.positive_numer;
+        let gap = if kq >= rest_p {
+            F::new(&kq - &rest_p, kq.clone())
+        } else {
+            -F::new(&rest_p - &kq, kq.clone())
+        };
+        let curvature = self.sqrt(
+            BigUint::from(outcomes) * BigUint::from(rest),
+            BigUint::from(experiments),
+        )?;
+        let u = gap * curvature;
+        let w = signed(w);
+
+        let tail = half - signed(laplace)
+            + density
+                * (F::from(1u32) / u - F::from(1u32) / w);
+
+        Ok((tail, exp_iterations.max(series_iterations)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        decimal, ratio, relative_error, settings,
+    };
+    use crate::{
+        bernoulli, bernoulli_interval, Cancellation,
+    };
+
+    #[test]
+    fn saddle_point_is_exact_at_the_ends() {
+        let cancellation = Cancellation::new();
+        for k in [0, 20] {
+            let exact = bernoulli(
+                20,
+                k,
+                ratio(1, 3),
+                &cancellation,
+            )
+            .unwrap()
+            .probability;
+            let result = saddle_point(
+                20,
+                k,
+                ratio(1, 3),
+                settings(&cancellation),
+            )
+            .unwrap();
+            assert_eq!(result.probability, exact);
+        }
+    }
+
+    #[test]
+    fn saddle_point_is_close_to_the_exact_value() {
+        let cancellation = Cancellation::new();
+        let exact =
+            bernoulli(200, 70, ratio(3, 10), &cancellation)
+                .unwrap()
+                .probability;
+        let result = saddle_point(
+            200,
+            70,
+            ratio(3, 10),
+            settings(&cancellation),
+        )
+        .unwrap();
+        assert!(
+            relative_error(&result.probability, &exact)
+                < decimal("0.01")
+        );
+
+        let exact = bernoulli_interval(
+            100,
+            45,
+            55,
+            ratio(1, 2),
+            &cancellation,
+        )
+        .unwrap()
+        .probability;
+        let result = saddle_point_interval(
+            100,
+            45,
+            55,
+            ratio(1, 2),
+            settings(&cancellation),
+        )
+        .unwrap();
+        assert!(
+            relative_error(&result.probability, &exact)
+                < decimal("0.0001")
+        );
+    }
+}
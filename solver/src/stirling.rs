@@ -0,0 +1,297 @@
+use fraction::{GenericFraction, Zero};
+use num_bigint::BigUint;
+use num_rational::Ratio;
+use web_time::Instant;
+
+use crate::{
+    exp_reduced, ln, pi, sqrt_bounds, sqrt_to_digits, took,
+    validate_outcomes, validate_probability, Bounds,
+    Cancellation, Exp, Partial, Progress, Settings,
+    SolverError, SolverResult, Stage,
+};
+
+type F = GenericFraction<BigUint>;
+
+// B(2j) / (2j (2j - 1)) for j = 1..=10, the coefficients of
+// 1 / m^(2j-1) in the Stirling series
+const COEFFICIENTS: [(i64, u64); 10] = [
+    (1, 12),
+    (-1, 360),
+    (1, 1260),
+    (-1, 1680),
+    (1, 1188),
+    (-691, 360360),
+    (1, 156),
+    (-3617, 122400),
+    (43867, 244188),
+    (-174611, 125400),
+];
+
+// Most correction terms stirling can take, one coefficient is
+// kept for the remainder
+pub const MAX_STIRLING_TERMS: usize =
+    COEFFICIENTS.len() - 1;
+
+// P(X = k) with every factorial replaced by the Stirling series
+// ln m! = m ln m - m + ln(2πm) / 2 + θ(m), which comes down to
+// P(X = k) = sqrt(n / (2π k (n - k))) * e^E with
+// E = k ln(np / k) + (n - k) ln(nq / (n - k)) + θ(n) - θ(k) - θ(n - k).
+//
+// θ(m) is summed to `terms` corrections, at most
+// MAX_STIRLING_TERMS. The series alternates with every partial
+// sum on the other side of θ(m), so θ(m) lies between the sums of
+// `terms` and `terms + 1` corrections. Together with the
+// enclosures of ln, exp, sqrt and pi this gives guaranteed bounds,
+// the probability is their midpoint. Only the digits, progress
+// and cancellation of the settings are read
+pub fn stirling(
+    experiments: u32,
+    positive_outcomes: u32,
+    positive_probability: Ratio<BigUint>,
+    terms: usize,
+    settings: Settings,
+) -> Result<SolverResult, SolverError> {
+    let now = Instant::now();
+    let Settings {
+        digits,
+        progress,
+        cancellation,
+        ..
+    } = settings;
+
+    validate_outcomes(experiments, positive_outcomes)?;
+    validate_probability(&positive_probability)?;
+
+    let terms = terms.min(MAX_STIRLING_TERMS);
+    let (positive_numer, prob_denom) =
+        positive_probability.into();
+    let negative_numer = &prob_denom - &positive_numer;
+    let rest = experiments - positive_outcomes;
+
+    // C(n, k) is 1 at the ends and p^k q^(n-k) vanishes in between
+    // for p = 0 or 1, so these need no factorials at all
+    if positive_outcomes == 0
+        || rest == 0
+        || positive_numer.is_zero()
+        || negative_numer.is_zero()
+    {
+        let exact = F::new(
+            positive_numer.pow(positive_outcomes)
+                * negative_numer.pow(rest),
+            prob_denom.pow(experiments),
+        );
+        return Ok(SolverResult {
+            probability: exact.clone(),
+            took: took(now.elapsed())?,
+            iterations: terms as u32,
+            bounds: Some(Bounds {
+                lower: exact.clone(),
+                upper: exact,
+            }),
+        });
+    }
+
+    // k and n - k multiply the logarithms, which costs their
+    // digits of absolute precision
+    let ln_digits =
+        digits + experiments.to_string().len() + 2;
+
+    let (first_lower, first_upper) = ln(
+        experiments * &positive_numer,
+        positive_outcomes * &prob_denom,
+        ln_digits,
+        cancellation,
+    )?;
+    let (second_lower, second_upper) = ln(
+        experiments * &negative_numer,
+        rest * &prob_denom,
+        ln_digits,
+        cancellation,
+    )?;
+    let (whole_lower, whole_upper) =
+        theta(experiments, terms);
+    let (outcomes_lower, outcomes_upper) =
+        theta(positive_outcomes, terms);
+    let (rest_lower, rest_upper) = theta(rest, terms);
+
+    let exponent_lower = F::from(positive_outcomes)
+        * first_lower
+        + F::from(rest) * second_lower
+        + whole_lower
+        - outcomes_upper
+        - rest_upper;
+    let exponent_upper = F::from(positive_outcomes)
+        * first_upper
+        + F::from(rest) * second_upper
+        + whole_upper
+        - outcomes_lower
+        - rest_lower;
+
+    let exp_progress = |step: Progress| {
+        progress(Partial::new(Stage::Exp, &step, None))
+    };
+    let (exp_lower, _) = signed_exp(
+        exponent_lower,
+        digits,
+        &exp_progress,
+        cancellation,
+    )?;
+    let (_, exp_upper) = signed_exp(
+        exponent_upper,
+        digits,
+        &exp_progress,
+        cancellation,
+    )?;
+
+    // sqrt(n / (2π k (n - k))), the larger pi gives the lower end
+    let pi = pi(digits);
+    let (pi_lower, pi_upper) = (pi.lower(), pi.upper());
+    let spread = 2u32
+        * BigUint::from(positive_outcomes)
+        * BigUint::from(rest);
+    let (guess_numer, guess_denom) = sqrt_to_digits(
+        experiments * &pi.denom,
+        &spread * &pi.numer,
+        digits,
+        &|step| {
+            progress(Partial::new(
+                Stage::SquareRoot,
+                &step,
+                None,
+            ))
+        },
+        cancellation,
+    )?;
+    let (root_lower, root_upper) = sqrt_bounds(
+        (experiments * pi_upper.1, &spread * pi_upper.0),
+        (experiments * pi_lower.1, &spread * pi_lower.0),
+        (&guess_numer, &guess_denom),
+    );
+
+    let lower =
+        F::new(root_lower.0, root_lower.1) * exp_lower;
+    let upper =
+        F::new(root_upper.0, root_upper.1) * exp_upper;
+    let probability =
+        (lower.clone() + upper.clone()) / F::from(2u32);
+
+    let elapsed = now.elapsed();
+
+    Ok(SolverResult {
+        probability,
+        took: took(elapsed)?,
+        iterations: terms as u32,
+        bounds: Some(Bounds { lower, upper }),
+    })
+}
+
+// Enclosure of e^x for a signed x, e^-x = 1 / e^x
+fn signed_exp(
+    exponent: F,
+    digits: usize,
+    progress: &dyn Fn(Progress),
+    cancellation: &Cancellation,
+) -> Result<(F, F), SolverError> {
+    let GenericFraction::Rational(sign, exponent) =
+        exponent
+    else {
+        unreachable!("the exponent is a finite sum")
+    };
+    let (numer, denom) = exponent.into();
+    let Exp {
+        numer: lower,
+        denom: lower_denom,
+        upper,
+        ..
+    } = exp_reduced(
+        numer,
+        denom,
+        digits,
+        progress,
+        cancellation,
+    )?;
+    let (upper, upper_denom) =
+        upper.expect("exp_reduced always bounds e^x");
+
+    Ok(match sign {
+        fraction::Sign::Plus => (
+            F::new(lower, lower_denom),
+            F::new(upper, upper_denom),
+        ),
+        fraction::Sign::Minus => (
+            F::new(upper_denom, upper),
+            F::new(lower_denom, lower),
+        ),
+    })
+}
+
+// Stirling corrections θ(m) enclosed between the sums of `terms`
+// and `terms + 1` of them
+fn theta(m: u32, terms: usize) -> (F, F) {
+    let mut sum = F::zero();
+    let mut sums = Vec::with_capacity(terms + 2);
+    sums.push(sum.clone());
+    for (j, (numer, denom)) in
+        COEFFICIENTS.iter().take(terms + 1).enumerate()
+    {
+        let term = F::new(
+            BigUint::from(numer.unsigned_abs()),
+            BigUint::from(*denom)
+                * BigUint::from(m).pow(2 * j as u32 + 1),
+        );
+        sum = if *numer < 0 {
+            sum - term
+        } else {
+            sum + term
+        };
+        sums.push(sum.clone());
+    }
+
+    let (with, next) = (&sums[terms], &sums[terms + 1]);
+    if with <= next {
+        (with.clone(), next.clone())
+    } else {
+        (next.clone(), with.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bernoulli;
+    use crate::tests::{ratio, settings};
+
+    #[test]
+    fn stirling_bounds_enclose_the_exact_value() {
+        let cancellation = Cancellation::new();
+        for (n, k, p) in [
+            (10, 3, ratio(1, 2)),
+            (100, 37, ratio(3, 10)),
+            (500, 1, ratio(1, 100)),
+        ] {
+            for terms in [0, 3] {
+                let exact = bernoulli(
+                    n,
+                    k,
+                    p.clone(),
+                    &cancellation,
+                )
+                .unwrap()
+                .probability;
+                let result = stirling(
+                    n,
+                    k,
+                    p.clone(),
+                    terms,
+                    settings(&cancellation),
+                )
+                .unwrap();
+                let bounds = result.bounds.unwrap();
+                assert!(
+                    bounds.lower <= exact
+                        && exact <= bounds.upper
+                );
+            }
+        }
+    }
+}
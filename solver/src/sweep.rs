@@ -0,0 +1,280 @@
+use fraction::{GenericFraction, Zero};
+use num_bigint::BigUint;
+use num_rational::Ratio;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bernoulli, leading_digits, moivre_laplace,
+    Cancellation, Iterations, Partial, Progress, Settings,
+    SolverError, SquareRoot, Stage,
+};
+
+// Inclusive range from..=to, a step of 0 is treated as 1
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Steps {
+    pub from: u32,
+    pub to: u32,
+    pub step: u32,
+}
+
+impl Steps {
+    pub fn single(value: u32) -> Self {
+        Self {
+            from: value,
+            to: value,
+            step: 1,
+        }
+    }
+
+    pub fn values(self) -> impl Iterator<Item = u32> {
+        (self.from..=self.to)
+            .step_by(self.step.max(1) as usize)
+    }
+
+    pub fn len(self) -> usize {
+        self.values().count()
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.from > self.to
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub enum Outcomes {
+    Range(Steps),
+    // Integer closest to np, where the distribution peaks
+    Mean,
+    // The mode floor((n + 1) p) moved by this many standard
+    // deviations sqrt(npq), rounded and kept within 0..=n
+    Deviations(f64),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Sweep {
+    pub experiments: Steps,
+    pub outcomes: Outcomes,
+    // p goes over numerators / denominator
+    pub numerators: Steps,
+    pub denominator: u32,
+    pub iterations: Iterations,
+    pub square_root: SquareRoot,
+    pub pi_digits: usize,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SweepPoint {
+    pub experiments: u32,
+    pub outcomes: u32,
+    pub probability: GenericFraction<BigUint>,
+    pub exact: GenericFraction<BigUint>,
+    pub approximation: GenericFraction<BigUint>,
+    pub absolute_error: GenericFraction<BigUint>,
+    // None when the exact value is zero
+    pub relative_error: Option<GenericFraction<BigUint>>,
+    // Leading significant digits the approximation shares with
+    // the exact value, None when they are equal
+    pub agreeing_digits: Option<usize>,
+}
+
+impl Sweep {
+    // Every (n, k, p) of the sweep, points where k > n or p is 0
+    // or 1 are left out since the approximation is undefined there
+    pub fn points(&self) -> Vec<(u32, u32, u32)> {
+        let mut points = Vec::new();
+        for numerator in self.numerators.values() {
+            if numerator == 0
+                || numerator >= self.denominator
+            {
+                continue;
+            }
+            for experiments in self.experiments.values() {
+                let outcomes: Vec<u32> = match self.outcomes
+                {
+                    Outcomes::Range(steps) => {
+                        steps.values().collect()
+                    }
+                    Outcomes::Mean => {
+                        let np = experiments as u64
+                            * numerator as u64;
+                        let denominator =
+                            self.denominator as u64;
+                        vec![
+                            ((2 * np + denominator)
+                                / (2 * denominator))
+                                as u32,
+                        ]
+                    }
+                    Outcomes::Deviations(offset) => {
+                        let n = experiments as u64;
+                        let numerator = numerator as u64;
+                        let denominator =
+                            self.denominator as u64;
+                        let mode = (n + 1) * numerator
+                            / denominator;
+                        let deviation = (n as f64
+                            * numerator as f64
+                            * (denominator - numerator)
+                                as f64)
+                            .sqrt()
+                            / denominator as f64;
+                        let outcomes = (mode as f64
+                            + offset * deviation)
+                            .round()
+                            .clamp(0.0, n as f64);
+                        vec![outcomes as u32]
+                    }
+                };
+                for outcomes in outcomes {
+                    if outcomes <= experiments {
+                        points.push((
+                            experiments,
+                            outcomes,
+                            numerator,
+                        ));
+                    }
+                }
+            }
+        }
+        points
+    }
+}
+
+// Runs bernoulli and moivre_laplace over every point of the
+// sweep. Progress is reported once per finished point
+pub fn sweep(
+    sweep: &Sweep,
+    progress: &dyn Fn(Partial),
+    cancellation: &Cancellation,
+) -> Result<Vec<SweepPoint>, SolverError> {
+    let points = sweep.points();
+    let total = points.len();
+
+    let mut results = Vec::with_capacity(total);
+    for (experiments, outcomes, numerator) in points {
+        let probability = Ratio::new_raw(
+            BigUint::from(numerator),
+            BigUint::from(sweep.denominator),
+        );
+
+        let exact = bernoulli(
+            experiments,
+            outcomes,
+            probability.clone(),
+            cancellation,
+        )?
+        .probability;
+        let approximation = moivre_laplace(
+            experiments,
+            outcomes,
+            probability.clone(),
+            Settings {
+                iterations: sweep.iterations,
+                square_root: sweep.square_root,
+                pi_digits: sweep.pi_digits,
+                // Only read by stirling, which sweeps never run
+                digits: sweep.pi_digits,
+                progress: &|_| {},
+                cancellation,
+            },
+        )?
+        .probability;
+
+        results.push(compare(
+            experiments,
+            outcomes,
+            probability,
+            exact,
+            approximation,
+        ));
+
+        progress(Partial::new(
+            Stage::Sweep,
+            &Progress {
+                iteration: results.len(),
+                total: Some(total),
+                value: None,
+            },
+            None,
+        ));
+    }
+
+    Ok(results)
+}
+
+fn compare(
+    experiments: u32,
+    outcomes: u32,
+    probability: Ratio<BigUint>,
+    exact: GenericFraction<BigUint>,
+    approximation: GenericFraction<BigUint>,
+) -> SweepPoint {
+    let ratio = |fraction: &GenericFraction<BigUint>| {
+        match fraction {
+            GenericFraction::Rational(_, ratio) => {
+                ratio.clone()
+            }
+            _ => Ratio::new_raw(0u32.into(), 1u32.into()),
+        }
+    };
+    let fraction = |ratio| {
+        GenericFraction::Rational(
+            fraction::Sign::Plus,
+            ratio,
+        )
+    };
+
+    let (exact_ratio, approximation_ratio) =
+        (ratio(&exact), ratio(&approximation));
+    let absolute = if approximation_ratio >= exact_ratio {
+        approximation_ratio - &exact_ratio
+    } else {
+        exact_ratio.clone() - approximation_ratio
+    };
+
+    let relative = (!exact_ratio.is_zero())
+        .then(|| &absolute / &exact_ratio);
+
+    // With exact / error = v * 10^-shift for some 1 <= v < 10,
+    // the first -shift digits are the same, like in Bounds
+    let agreeing_digits =
+        (!absolute.is_zero()).then(|| {
+            let (_, shift) = leading_digits(
+                &(exact_ratio.numer() * absolute.denom()),
+                &(exact_ratio.denom() * absolute.numer()),
+                1,
+            );
+            (-shift).max(0) as usize
+        });
+
+    SweepPoint {
+        experiments,
+        outcomes,
+        probability: fraction(probability),
+        exact,
+        approximation,
+        absolute_error: fraction(absolute),
+        relative_error: relative.map(fraction),
+        agreeing_digits,
+    }
+}
+
+// log10 of a non negative fraction, good to about 15 digits even
+// when numer and denom are far too large for f64 on their own.
+// Zero gives negative infinity
+pub fn log10(fraction: &GenericFraction<BigUint>) -> f64 {
+    let GenericFraction::Rational(_, ratio) = fraction
+    else {
+        return f64::NAN;
+    };
+    if ratio.numer().is_zero() {
+        return f64::NEG_INFINITY;
+    }
+
+    let (digits, shift) =
+        leading_digits(ratio.numer(), ratio.denom(), 15);
+    // digits has exactly 15 digits, so it fits f64 exactly
+    let mantissa: f64 =
+        digits.to_string().parse().unwrap_or(1.0);
+    mantissa.log10() - shift as f64
+}
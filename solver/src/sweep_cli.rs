@@ -0,0 +1,409 @@
+use std::collections::HashMap;
+
+use fraction::GenericFraction;
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_rational::Ratio;
+use serde::Serialize;
+use solver::{
+    sweep, Cancellation, Outcomes, Steps, Sweep, SweepPoint,
+};
+
+use crate::{
+    batch::csv_field, parse_number, parse_probability,
+    parse_series_option, scientific, Options,
+};
+
+const USAGE: &str = "\
+Usage: solver sweep -n <range> -k <range|mean> -p <range> [options]
+
+Computes the exact Bernoulli value and the local Moivre-Laplace approximation
+for every combination of n, k and p, and writes their errors to stdout.
+Points where k > n, p = 0 or p = 1 are skipped.
+
+Ranges are written as from..to or from..to:step, both ends included, or as
+a single value. For p the bounds and the step are fractions or decimals,
+like 1/10..9/10:1/10 or 0.1..0.9:0.1
+
+Options:
+    -n <range>              Amounts of experiments
+    -k <range|mean>         Amounts of positive outcomes, mean picks the
+                            integer closest to np for every point
+    -p <range>              Probabilities of a positive outcome
+    --iterations, --until-stable, --reduce, --sqrt-iterations,
+    --sqrt-digits, --precision
+                            Same as for moivre-laplace
+    --format <F>            csv or jsonl [default: csv]";
+
+const CSV_HEADER: &str = "n,k,p,exact,approximation,\
+absolute_error,relative_error,agreeing_digits";
+
+// One point as written to stdout, with the fractions as
+// decimals so that JSONL holds the same values as the CSV
+#[derive(Serialize)]
+struct Row {
+    n: u32,
+    k: u32,
+    p: String,
+    exact: String,
+    approximation: String,
+    absolute_error: String,
+    // None when the exact value is zero
+    relative_error: Option<String>,
+    // None when the approximation is exact
+    agreeing_digits: Option<usize>,
+}
+
+impl Row {
+    fn new(point: &SweepPoint, precision: usize) -> Self {
+        Row {
+            n: point.experiments,
+            k: point.outcomes,
+            p: format!("{}", point.probability),
+            exact: scientific(&point.exact, precision),
+            approximation: scientific(
+                &point.approximation,
+                precision,
+            ),
+            absolute_error: scientific(
+                &point.absolute_error,
+                precision,
+            ),
+            relative_error: point
+                .relative_error
+                .as_ref()
+                .map(|error| scientific(error, precision)),
+            agreeing_digits: point.agreeing_digits,
+        }
+    }
+
+    fn csv(self) -> String {
+        [
+            self.n.to_string(),
+            self.k.to_string(),
+            self.p,
+            self.exact,
+            self.approximation,
+            self.absolute_error,
+            self.relative_error.unwrap_or_default(),
+            self.agreeing_digits
+                .map(|digits| digits.to_string())
+                .unwrap_or_else(|| "exact".into()),
+        ]
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",")
+    }
+}
+
+fn parse_steps(
+    flag: &str,
+    value: &str,
+) -> Result<Steps, String> {
+    let Some((from, rest)) = value.split_once("..") else {
+        return Ok(Steps::single(parse_number(
+            flag, value,
+        )?));
+    };
+    let (to, step) =
+        rest.split_once(':').unwrap_or((rest, "1"));
+
+    Ok(Steps {
+        from: parse_number(flag, from)?,
+        to: parse_number(flag, to)?,
+        step: parse_number(flag, step)?,
+    })
+}
+
+// The bounds and the step are brought to a common denominator,
+// so that the sweep can go over whole numerators
+fn parse_probability_steps(
+    value: &str,
+) -> Result<(Steps, u32), String> {
+    let (from, to, step) = match value.split_once("..") {
+        Some((from, rest)) => {
+            let (to, step) =
+                rest.split_once(':').unwrap_or((rest, ""));
+            (from, to, (!step.is_empty()).then_some(step))
+        }
+        None => (value, value, None),
+    };
+
+    let from = parse_probability(from)?;
+    let to = parse_probability(to)?;
+    let step = match step {
+        Some(step) => parse_probability(step)?,
+        // One step of the finer of the two denominators
+        None => Ratio::new_raw(
+            1u32.into(),
+            from.denom().lcm(to.denom()),
+        ),
+    };
+
+    let denominator =
+        from.denom().lcm(to.denom()).lcm(step.denom());
+    let numerator = |ratio: &Ratio<BigUint>| {
+        u32::try_from(
+            ratio.numer() * (&denominator / ratio.denom()),
+        )
+        .map_err(|_| {
+            format!("Probability {} is too precise", value)
+        })
+    };
+
+    Ok((
+        Steps {
+            from: numerator(&from)?,
+            to: numerator(&to)?,
+            step: numerator(&step)?,
+        },
+        u32::try_from(&denominator).map_err(|_| {
+            format!("Probability {} is too precise", value)
+        })?,
+    ))
+}
+
+const HEATMAP_USAGE: &str = "\
+Usage: solver heatmap -n <range> -p <range> [--offset <D>] [options]
+
+Computes the relative error of the local Moivre-Laplace approximation against
+the exact Bernoulli value on a grid of n and p, and writes it to stdout as CSV
+with one row per n and one column per p. Cells where p = 0 or p = 1 are left
+empty, as are cells where the exact value is zero.
+
+Ranges are written like for sweep.
+
+Options:
+    -n <range>              Amounts of experiments
+    -p <range>              Probabilities of a positive outcome
+    --offset <D>            k is the mode floor((n + 1) p) moved by D
+                            standard deviations sqrt(npq) [default: 0]
+    --iterations, --until-stable, --reduce, --sqrt-iterations,
+    --sqrt-digits, --precision
+                            Same as for moivre-laplace";
+
+// Options shared by sweep and heatmap. Heatmap has no -k or
+// --format, and picks k with --offset instead
+struct Parsed {
+    sweep: Sweep,
+    precision: usize,
+    jsonl: bool,
+}
+
+fn parse(
+    mut args: impl Iterator<Item = String>,
+    heatmap: bool,
+) -> Result<Option<Parsed>, String> {
+    let usage = if heatmap { HEATMAP_USAGE } else { USAGE };
+    let mut options = Options::default();
+    let mut experiments = None;
+    let mut outcomes = None;
+    let mut probabilities = None;
+    let mut jsonl = false;
+
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "-h" | "--help" => {
+                println!("{}", usage);
+                return Ok(None);
+            }
+            "--reduce" => {
+                options.reduce = true;
+                continue;
+            }
+            _ => {}
+        }
+
+        let value = args.next().ok_or_else(|| {
+            format!("{} expects a value", flag)
+        })?;
+
+        match flag.as_str() {
+            "-n" => {
+                experiments =
+                    Some(parse_steps(&flag, &value)?)
+            }
+            "-k" if !heatmap => {
+                outcomes = Some(match value.as_str() {
+                    "mean" => Outcomes::Mean,
+                    _ => Outcomes::Range(parse_steps(
+                        &flag, &value,
+                    )?),
+                })
+            }
+            "--offset" if heatmap => {
+                outcomes = Some(Outcomes::Deviations(
+                    parse_number(&flag, &value)?,
+                ))
+            }
+            "-p" => {
+                probabilities =
+                    Some(parse_probability_steps(&value)?)
+            }
+            "--format" if !heatmap => {
+                jsonl = match value.as_str() {
+                    "csv" => false,
+                    "jsonl" => true,
+                    _ => {
+                        return Err(format!(
+                            "Unknown format {}",
+                            value
+                        ))
+                    }
+                }
+            }
+            _ => {
+                if !parse_series_option(
+                    &mut options,
+                    &flag,
+                    &value,
+                )? {
+                    return Err(format!(
+                        "Unknown option {}\n\n{}",
+                        flag, usage
+                    ));
+                }
+            }
+        }
+    }
+
+    if heatmap {
+        outcomes.get_or_insert(Outcomes::Deviations(0.0));
+    }
+
+    let missing =
+        |flag| format!("Missing {}\n\n{}", flag, usage);
+    let (numerators, denominator) =
+        probabilities.ok_or_else(|| missing("-p"))?;
+
+    Ok(Some(Parsed {
+        sweep: Sweep {
+            experiments: experiments
+                .ok_or_else(|| missing("-n"))?,
+            outcomes: outcomes
+                .ok_or_else(|| missing("-k"))?,
+            numerators,
+            denominator,
+            iterations: options.exponent_iterations(),
+            square_root: options.square_root(),
+            pi_digits: options.precision,
+        },
+        precision: options.precision,
+        jsonl,
+    }))
+}
+
+pub fn run(
+    args: impl Iterator<Item = String>,
+) -> Result<(), String> {
+    let Some(Parsed {
+        sweep: sweep_options,
+        precision,
+        jsonl,
+    }) = parse(args, false)?
+    else {
+        return Ok(());
+    };
+
+    let points = sweep(
+        &sweep_options,
+        &|_| {},
+        &Cancellation::new(),
+    )
+    .map_err(|error| error.to_string())?;
+
+    if !jsonl {
+        println!("{}", CSV_HEADER);
+    }
+    for point in points {
+        let row = Row::new(&point, precision);
+        if jsonl {
+            println!(
+                "{}",
+                serde_json::to_string(&row).expect(
+                    "rows only hold strings and numbers"
+                )
+            );
+        } else {
+            println!("{}", row.csv());
+        }
+    }
+
+    Ok(())
+}
+
+pub fn heatmap(
+    args: impl Iterator<Item = String>,
+) -> Result<(), String> {
+    let Some(Parsed {
+        sweep: sweep_options,
+        precision,
+        ..
+    }) = parse(args, true)?
+    else {
+        return Ok(());
+    };
+
+    let points = sweep(
+        &sweep_options,
+        &|_| {},
+        &Cancellation::new(),
+    )
+    .map_err(|error| error.to_string())?;
+
+    let denominator =
+        BigUint::from(sweep_options.denominator);
+    let columns: Vec<u32> =
+        sweep_options.numerators.values().collect();
+    let header = columns.iter().map(|numerator| {
+        csv_field(
+            &Ratio::new(
+                BigUint::from(*numerator),
+                denominator.clone(),
+            )
+            .to_string(),
+        )
+    });
+    println!("n,{}", header.collect::<Vec<_>>().join(","));
+
+    // Cells are looked up by n and the numerator of p, since
+    // the sweep leaves out the points where p is 0 or 1
+    let cells: HashMap<(u32, u32), SweepPoint> = points
+        .into_iter()
+        .map(|point| {
+            let GenericFraction::Rational(_, probability) =
+                &point.probability
+            else {
+                unreachable!("sweeps only hold rationals")
+            };
+            let numerator = u32::try_from(
+                probability.numer() * &denominator
+                    / probability.denom(),
+            )
+            .expect("numerators come from u32");
+            ((point.experiments, numerator), point)
+        })
+        .collect();
+
+    for experiments in sweep_options.experiments.values() {
+        let row = columns.iter().map(|numerator| {
+            cells
+                .get(&(experiments, *numerator))
+                .and_then(|point| {
+                    point.relative_error.as_ref()
+                })
+                .map(|error| scientific(error, precision))
+                .unwrap_or_default()
+        });
+        println!(
+            "{},{}",
+            experiments,
+            row.map(|field| csv_field(&field))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+    }
+
+    Ok(())
+}
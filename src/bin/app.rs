@@ -1,73 +1,93 @@
 use fraction::BigFraction;
-use gloo_worker::Spawnable;
 
 use leptos::{
-    create_effect, event_target, store_value, CollectView,
-    RwSignal,
+    create_effect, event_target, event_target_checked,
+    store_value, CollectView, RwSignal, StoredValue,
 };
 use leptos::{create_rw_signal, ReadSignal, Signal};
 
-use fraction::GenericFraction;
+use fraction::{GenericFraction, One};
 
 use leptos::{
-    component, create_signal, view, IntoView, SignalGet,
+    component, view, IntoView, SignalGet,
     SignalGetUntracked, SignalSet,
 };
 use num_bigint::BigUint;
 use num_rational::Ratio;
 
-use solver::SolverResult;
+use solver::{
+    error_bounds, log10, Cancellation, Distribution,
+    Outcomes, Partial, SolverResult, Start, Steps, Sweep,
+    SweepPoint,
+};
 
 use bernoulli_vs_moivre_laplace::{
-    BernoulliSolver, MoivreLaplaceSolver, SolverRequest,
+    Envelope, FromWorker, Method, SolverRequest,
+    SolverWorker, ToWorker, WorkerError,
 };
 
 #[component]
 pub fn App() -> impl IntoView {
-    let (bernoulli_result, set_bernoulli_result) =
-        create_signal::<Option<SolverResult>>(None);
-    let (bernoulli_running, set_bernoulli_running) =
-        create_signal(false);
-    let (
-        moivre_laplace_running,
-        set_moivre_laplace_running,
-    ) = create_signal(false);
-    let (moivre_laplace_result, set_moivre_laplace_result) =
-        create_signal::<Option<SolverResult>>(None);
-
-    let bernoulli_solver = BernoulliSolver::spawner()
-        .callback(move |result| {
-            set_bernoulli_result(Some(result));
-            set_bernoulli_running(false)
-        })
-        .spawn("./bernoulli.js");
-    let bernoulli_solver =
-        Box::leak(Box::new(bernoulli_solver));
+    let bernoulli = SolverPanel::new(Method::Bernoulli);
+    let moivre_laplace =
+        SolverPanel::new(Method::MoivreLaplace);
+    let continuity_corrected =
+        SolverPanel::new(Method::ContinuityCorrected);
+    let edgeworth = SolverPanel::new(Method::Edgeworth);
+    let stirling = SolverPanel::new(Method::Stirling);
+    let saddle_point =
+        SolverPanel::new(Method::SaddlePoint);
+    let poisson = SolverPanel::new(Method::Poisson);
+    let sweep = JobPanel::new(|message| match message {
+        FromWorker::Swept(points) => Some(points),
+        _ => None,
+    });
+    let heatmap = JobPanel::new(|message| match message {
+        FromWorker::Swept(points) => Some(points),
+        _ => None,
+    });
+    let distribution =
+        JobPanel::new(|message| match message {
+            FromWorker::Distributed(values) => Some(values),
+            _ => None,
+        });
 
     let iterations_done = create_rw_signal(0);
 
-    let moivre_laplace_solver =
-        MoivreLaplaceSolver::spawner()
-            .callback(move |result| {
-                iterations_done.set(result.iterations);
-                set_moivre_laplace_result(Some(result));
-                set_moivre_laplace_running(false);
-            })
-            .spawn("./moivre_laplace.js");
-    let moivre_laplace_solver =
-        Box::leak(Box::new(moivre_laplace_solver));
+    create_effect(move |_| {
+        if let Some(result) =
+            moivre_laplace.job.output.get()
+        {
+            iterations_done.set(
+                result
+                    .map(|result| result.iterations)
+                    .unwrap_or_default(),
+            );
+        }
+    });
+
+    // Every Calculate gets a new id, the workers are separate so
+    // they can share it
+    let next_id = store_value(0u64);
 
     let variables = Variables {
         total_experiments: 100.into(),
         required_to_pass: 50.into(),
+        interval: false.into(),
+        required_to_pass_max: 100.into(),
         denominator: 100.into(),
         pass_numerator: 80.into(),
         fail_numerator: 20.into(),
         precision: 1000.into(),
         iterations: 300.into(),
         automatic_iterations: false.into(),
+        reduce_exponent: false.into(),
         stable_amount: 5.into(),
         sqrt_iterations: 10.into(),
+        sqrt_to_digits: false.into(),
+        sqrt_digits: 50.into(),
+        edgeworth_order: 2.into(),
+        stirling_terms: 3.into(),
     };
 
     let np = Signal::derive(move || {
@@ -104,12 +124,20 @@ pub fn App() -> impl IntoView {
         fail_updated.set_value(true);
         leptos::log!(
             "Setting pass: {}",
-            variables.denominator.get_untracked()
-                - variables.fail_numerator.get()
+            variables
+                .denominator
+                .get_untracked()
+                .saturating_sub(
+                    variables.fail_numerator.get()
+                )
         );
         variables.pass_numerator.set(
-            variables.denominator.get_untracked()
-                - variables.fail_numerator.get(),
+            variables
+                .denominator
+                .get_untracked()
+                .saturating_sub(
+                    variables.fail_numerator.get(),
+                ),
         );
     });
 
@@ -124,13 +152,21 @@ pub fn App() -> impl IntoView {
         }
         leptos::log!(
             "Setting fail: {}",
-            variables.denominator.get_untracked()
-                - variables.pass_numerator.get()
+            variables
+                .denominator
+                .get_untracked()
+                .saturating_sub(
+                    variables.pass_numerator.get()
+                )
         );
         pass_updated.set_value(true);
         variables.fail_numerator.set(
-            variables.denominator.get_untracked()
-                - variables.pass_numerator.get(),
+            variables
+                .denominator
+                .get_untracked()
+                .saturating_sub(
+                    variables.pass_numerator.get(),
+                ),
         );
     });
 
@@ -149,50 +185,264 @@ pub fn App() -> impl IntoView {
             <DerivedVariables variables=derived_variables/>
             <button
                 on:click=move |_| {
-                    let request = SolverRequest {
-                        total: variables.total_experiments.get_untracked(),
-                        required: variables.required_to_pass.get_untracked(),
-                        odds: Ratio::new_raw(
-                            variables.pass_numerator.get_untracked().into(),
-                            variables.denominator.get_untracked().into(),
-                        ),
-                        precision: variables.precision.get_untracked(),
-                        iterations: variables.iterations.get_untracked(),
-                        stable_amount: variables.stable_amount.get_untracked(),
-                        sqrt_iterations: variables.sqrt_iterations.get_untracked(),
-                    };
-                    bernoulli_solver.send(request.clone());
-                    set_bernoulli_running(true);
-                    moivre_laplace_solver.send(request);
-                    set_moivre_laplace_running(true);
+                    let request = variables.request();
+                    let id = next_id.get_value();
+                    next_id.set_value(id + 1);
+                    bernoulli.send(id, request.clone());
+                    moivre_laplace.send(id, request.clone());
+                    continuity_corrected.send(id, request.clone());
+                    edgeworth.send(id, request.clone());
+                    stirling.send(id, request.clone());
+                    saddle_point.send(id, request.clone());
+                    distribution
+                        .send(
+                            id,
+                            ToWorker::Distribution(
+                                request.total,
+                                request.odds.clone(),
+                                Start::Mode,
+                                Some(CHART_DEVIATIONS),
+                            ),
+                        );
+                    poisson.send(id, request);
                 }
 
                 class="bg-blue-500 rounded mt-2 h-10 text-2xl"
             >
                 Calculate
             </button>
-            <div class="grid grid-cols-2 child:border-2 child:border-black gap-2 p-2 child:rounded child:grow child:p-2">
+            <button
+                on:click=move |_| {
+                    bernoulli.cancel();
+                    moivre_laplace.cancel();
+                    continuity_corrected.cancel();
+                    edgeworth.cancel();
+                    stirling.cancel();
+                    saddle_point.cancel();
+                    poisson.cancel();
+                    distribution.cancel();
+                }
+
+                disabled=move || {
+                    !(bernoulli.job.running.get() || moivre_laplace.job.running.get()
+                        || continuity_corrected.job.running.get() || edgeworth.job.running.get()
+                        || stirling.job.running.get() || saddle_point.job.running.get()
+                        || poisson.job.running.get() || distribution.running.get())
+                }
+                class="bg-red-500 disabled:bg-gray-300 rounded mt-2 h-10 text-2xl"
+            >
+                Cancel
+            </button>
+            <div class="grid grid-cols-2 xl:grid-cols-3 child:border-2 child:border-black gap-2 p-2 child:rounded child:grow child:p-2">
                 <ResultDisplay
                     precision=variables.precision
-                    result=bernoulli_result
-                    running=bernoulli_running
+                    result=bernoulli.job.output.read_only()
+                    progress=bernoulli.job.progress.read_only()
+                    running=bernoulli.job.running.read_only()
                     label="Bernoulli"
                 />
                 <ResultDisplay
                     precision=variables.precision
-                    result=moivre_laplace_result
-                    running=moivre_laplace_running
+                    result=moivre_laplace.job.output.read_only()
+                    progress=moivre_laplace.job.progress.read_only()
+                    running=moivre_laplace.job.running.read_only()
                     label="Moivre Laplace"
                 />
+                <ResultDisplay
+                    precision=variables.precision
+                    result=continuity_corrected.job.output.read_only()
+                    progress=continuity_corrected.job.progress.read_only()
+                    running=continuity_corrected.job.running.read_only()
+                    label="Continuity Corrected"
+                />
+                <ResultDisplay
+                    precision=variables.precision
+                    result=edgeworth.job.output.read_only()
+                    progress=edgeworth.job.progress.read_only()
+                    running=edgeworth.job.running.read_only()
+                    label="Edgeworth"
+                />
+                <ResultDisplay
+                    precision=variables.precision
+                    result=stirling.job.output.read_only()
+                    progress=stirling.job.progress.read_only()
+                    running=stirling.job.running.read_only()
+                    label="Stirling"
+                />
+                <ResultDisplay
+                    precision=variables.precision
+                    result=saddle_point.job.output.read_only()
+                    progress=saddle_point.job.progress.read_only()
+                    running=saddle_point.job.running.read_only()
+                    label="Saddle Point"
+                />
+                <ResultDisplay
+                    precision=variables.precision
+                    result=poisson.job.output.read_only()
+                    progress=poisson.job.progress.read_only()
+                    running=poisson.job.running.read_only()
+                    label="Poisson"
+                />
             </div>
+            <ErrorBoundsView
+                variables
+                exact=bernoulli.job.output.read_only()
+                approximation=moivre_laplace.job.output.read_only()
+            />
+            <DistributionView variables panel=distribution/>
+            <SweepView variables panel=sweep next_id/>
+            <HeatmapView variables panel=heatmap next_id/>
         </div>
     }
 }
 
+// Signals behind one of the panels, along with the worker that
+// fills them in. answer picks the output out of the messages of
+// the worker, and returns None for any other kind of answer
+pub struct JobPanel<T: 'static> {
+    pub output: RwSignal<Option<Result<T, WorkerError>>>,
+    pub progress: RwSignal<Option<Partial>>,
+    pub running: RwSignal<bool>,
+    // Id of the request the panel is waiting on. Responses with
+    // any other id belong to requests that were replaced or
+    // cancelled since and get dropped
+    pub current: RwSignal<Option<u64>>,
+    pub worker: StoredValue<SolverWorker>,
+    answer: fn(FromWorker) -> Option<T>,
+}
+
+// Derive would require T: Copy, the signals are Copy for any T
+impl<T> Clone for JobPanel<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for JobPanel<T> {}
+
+impl<T> JobPanel<T> {
+    pub fn new(
+        answer: fn(FromWorker) -> Option<T>,
+    ) -> Self {
+        let output = create_rw_signal(None);
+        let progress = create_rw_signal(None);
+        let running = create_rw_signal(false);
+        let current = create_rw_signal(None);
+        let worker = store_value(Self::spawn(
+            answer, output, progress, running, current,
+        ));
+
+        Self {
+            output,
+            progress,
+            running,
+            current,
+            worker,
+            answer,
+        }
+    }
+
+    fn spawn(
+        answer: fn(FromWorker) -> Option<T>,
+        output: RwSignal<Option<Result<T, WorkerError>>>,
+        progress: RwSignal<Option<Partial>>,
+        running: RwSignal<bool>,
+        current: RwSignal<Option<u64>>,
+    ) -> SolverWorker {
+        SolverWorker::spawn(
+            "./solver.js",
+            move |envelope: Envelope<FromWorker>| {
+                if current.get_untracked()
+                    != Some(envelope.id)
+                {
+                    return;
+                }
+
+                let outcome = match envelope.message {
+                    FromWorker::Progress(partial) => {
+                        progress.set(Some(partial));
+                        return;
+                    }
+                    FromWorker::Failed(error) => Err(error),
+                    message => match answer(message) {
+                        Some(answered) => Ok(answered),
+                        None => return,
+                    },
+                };
+                output.set(Some(outcome));
+                running.set(false);
+                current.set(None);
+            },
+        )
+        .expect("the worker is built next to the app")
+    }
+
+    // A busy worker would only read the message once its
+    // current job is done, so that job gets cancelled first
+    pub fn send(&self, id: u64, message: ToWorker) {
+        if self.running.get_untracked() {
+            self.cancel();
+        }
+        self.worker.with_value(|worker| {
+            worker.send(Envelope::new(id, message))
+        });
+        self.current.set(Some(id));
+        self.progress.set(None);
+        self.running.set(true);
+    }
+
+    // The worker can't read anything while a job runs, so the
+    // job is stopped by replacing the worker, dropping the old
+    // one terminates it
+    pub fn cancel(&self) {
+        self.current.set(None);
+        self.running.set(false);
+        self.worker.set_value(Self::spawn(
+            self.answer,
+            self.output,
+            self.progress,
+            self.running,
+            self.current,
+        ));
+    }
+}
+
+// One of the result panels, a job that runs a single method
+#[derive(Clone, Copy)]
+pub struct SolverPanel {
+    pub method: Method,
+    pub job: JobPanel<SolverResult>,
+}
+
+impl SolverPanel {
+    pub fn new(method: Method) -> Self {
+        Self {
+            method,
+            job: JobPanel::new(|message| match message {
+                FromWorker::Done(solved) => Some(solved),
+                _ => None,
+            }),
+        }
+    }
+
+    pub fn send(&self, id: u64, request: SolverRequest) {
+        self.job.send(
+            id,
+            ToWorker::Compute(self.method, request),
+        );
+    }
+
+    pub fn cancel(&self) {
+        self.job.cancel();
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct Variables {
     pub total_experiments: RwSignal<u32>,
     pub required_to_pass: RwSignal<u32>,
+    pub interval: RwSignal<bool>,
+    pub required_to_pass_max: RwSignal<u32>,
     pub denominator: RwSignal<u32>,
     pub pass_numerator: RwSignal<u32>,
     pub fail_numerator: RwSignal<u32>,
@@ -200,7 +450,59 @@ pub struct Variables {
     pub iterations: RwSignal<usize>,
     pub stable_amount: RwSignal<usize>,
     pub automatic_iterations: RwSignal<bool>,
+    pub reduce_exponent: RwSignal<bool>,
     pub sqrt_iterations: RwSignal<usize>,
+    pub sqrt_to_digits: RwSignal<bool>,
+    pub sqrt_digits: RwSignal<usize>,
+    pub edgeworth_order: RwSignal<u32>,
+    pub stirling_terms: RwSignal<usize>,
+}
+
+impl Variables {
+    pub fn request(&self) -> SolverRequest {
+        SolverRequest {
+            total: self.total_experiments.get_untracked(),
+            required: self.required_to_pass.get_untracked(),
+            interval: self.interval.get_untracked().then(
+                || {
+                    (
+                        self.required_to_pass
+                            .get_untracked(),
+                        self.required_to_pass_max
+                            .get_untracked(),
+                    )
+                },
+            ),
+            odds: Ratio::new_raw(
+                self.pass_numerator.get_untracked().into(),
+                self.denominator.get_untracked().into(),
+            ),
+            precision: self.precision.get_untracked(),
+            iterations: self.iterations.get_untracked(),
+            automatic_iterations: self
+                .automatic_iterations
+                .get_untracked(),
+            reduce_exponent: self
+                .reduce_exponent
+                .get_untracked(),
+            stable_amount: self
+                .stable_amount
+                .get_untracked(),
+            sqrt_iterations: self
+                .sqrt_iterations
+                .get_untracked(),
+            sqrt_to_digits: self
+                .sqrt_to_digits
+                .get_untracked(),
+            sqrt_digits: self.sqrt_digits.get_untracked(),
+            edgeworth_order: self
+                .edgeworth_order
+                .get_untracked(),
+            stirling_terms: self
+                .stirling_terms
+                .get_untracked(),
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -247,6 +549,29 @@ fn Variables(variables: Variables) -> impl IntoView {
                 label="Required To Pass(k)"
                 block=true
             />
+            <Toggle
+                value=variables.interval
+                id="interval"
+                label="Interval"
+                tooltip="Computes P(k <= X <= k2) instead of P(X = k)"
+                block=true
+            />
+            {move || {
+                variables
+                    .interval
+                    .get()
+                    .then(|| {
+                        view! {
+                            <Variable
+                                value=variables.required_to_pass_max
+                                id="required_to_pass_max"
+                                label="Required Up To(k2)"
+                                block=true
+                            />
+                        }
+                    })
+            }}
+
             <FractionVariable
                 numerator=variables.pass_numerator
                 denominator=variables.denominator
@@ -268,11 +593,51 @@ fn Variables(variables: Variables) -> impl IntoView {
                 block=true
             />
 
-            <Variable
-                value=variables.sqrt_iterations
-                id="sqrt"
-                label="Sqrt Iterations"
-                tooltip="exponential time to compute"
+            <Toggle
+                value=variables.sqrt_to_digits
+                id="sqrt_to_digits"
+                label="Sqrt To Digits"
+                tooltip="Stop the square root once it is within the requested digits"
+                block=true
+            />
+
+            {move || {
+                if variables.sqrt_to_digits.get() {
+                    view! {
+                        <Variable
+                            value=variables.sqrt_digits
+                            id="sqrt_digits"
+                            label="Sqrt Digits"
+                            tooltip="Correct numbers after the dot in the square root"
+                            block=true
+                        />
+                    }
+                } else {
+                    view! {
+                        <Variable
+                            value=variables.sqrt_iterations
+                            id="sqrt"
+                            label="Sqrt Iterations"
+                            tooltip="exponential time to compute"
+                            block=true
+                        />
+                    }
+                }
+            }}
+
+            <Toggle
+                value=variables.reduce_exponent
+                id="reduce_exponent"
+                label="Argument Reduction"
+                tooltip="Computes e^x as (e^(x/2^m))^(2^m) to Precision digits, overrides the iteration settings"
+                block=true
+            />
+
+            <Toggle
+                value=variables.automatic_iterations
+                id="automatic_iterations"
+                label="Automatic Iterations"
+                tooltip="Keep adding terms to the series until the result stops changing"
                 block=true
             />
 
@@ -283,7 +648,7 @@ fn Variables(variables: Variables) -> impl IntoView {
                             value=variables.stable_amount
                             id="stableamount"
                             label="Stable Number Amount"
-                            tooltip="Only for moivre laplace. Affects how many first non-zero digits need to be the same from the previous iteration"
+                            tooltip="Used by every method but Bernoulli and Stirling, for e^x and the Laplace series. Affects how many first non-zero digits need to be the same from the previous iteration"
                             block=true
                         />
                     }
@@ -293,13 +658,29 @@ fn Variables(variables: Variables) -> impl IntoView {
                             value=variables.iterations
                             id="iterations"
                             label="Exponent Iterations"
-                            tooltip="only affects moivre laplace. Bigger is slower but more accurate"
+                            tooltip="Used by every method but Bernoulli and Stirling, for e^x and the Laplace series. Bigger is slower but more accurate"
                             block=true
                         />
                     }
                 }
             }}
 
+            <Variable
+                value=variables.edgeworth_order
+                id="edgeworth_order"
+                label="Edgeworth Order"
+                tooltip="0 is the plain local theorem, 1 adds the skewness term, 2 the kurtosis terms"
+                block=true
+            />
+
+            <Variable
+                value=variables.stirling_terms
+                id="stirling_terms"
+                label="Stirling Terms"
+                tooltip="Corrections of the Stirling series, up to 9. The result is computed to Precision digits"
+                block=true
+            />
+
         </div>
     }
 }
@@ -349,9 +730,12 @@ pub fn Variable<N>(
     #[prop(optional)] block: bool,
 ) -> impl IntoView
 where
-    N: FromStr + Default + Clone + IntoView + 'static,
+    N: FromStr + Clone + IntoView + 'static,
 {
     use web_sys::HtmlDivElement;
+    // Text that doesn't parse keeps the previous value instead of
+    // silently turning into 0, and is marked until it is fixed
+    let invalid = create_rw_signal(false);
     let variable = view! {
         {label
             .map(|label| {
@@ -366,14 +750,13 @@ where
             contenteditable
             id=id
             on:focusout=move |ev| {
-                value
-                    .set(
-                        event_target::<HtmlDivElement>(&ev)
-                            .inner_text()
-                            .trim()
-                            .parse()
-                            .unwrap_or_default(),
-                    )
+                match event_target::<HtmlDivElement>(&ev).inner_text().trim().parse() {
+                    Ok(parsed) => {
+                        invalid.set(false);
+                        value.set(parsed);
+                    }
+                    Err(_) => invalid.set(true),
+                }
             }
 
             on:keypress=move |ev| {
@@ -385,10 +768,53 @@ where
             }
 
             class="hover:border-red-500 border-2 text-center rounded py-1"
+            class:border-red-500=move || invalid.get()
+            class:bg-red-100=move || invalid.get()
         >
 
             {move || value.get()}
         </div>
+        {move || {
+            invalid
+                .get()
+                .then(|| {
+                    view! { <p class="text-red-500 text-sm">Not a valid number</p> }
+                })
+        }}
+    };
+
+    if block {
+        view! { <div class="flex flex-col items-center">{variable}</div> }.into_view()
+    } else {
+        variable.into_view()
+    }
+}
+
+#[component]
+pub fn Toggle(
+    value: RwSignal<bool>,
+    #[prop(optional)] id: Option<&'static str>,
+    #[prop(optional)] label: Option<&'static str>,
+    #[prop(optional)] tooltip: Option<&'static str>,
+    #[prop(optional)] block: bool,
+) -> impl IntoView {
+    let variable = view! {
+        {label
+            .map(|label| {
+                view! {
+                    <label for=id>
+                        <span title=tooltip>{label}</span>
+                    </label>
+                }
+            })
+            .collect_view()}
+        <input
+            type="checkbox"
+            id=id
+            prop:checked=move || value.get()
+            on:change=move |ev| value.set(event_target_checked(&ev))
+            class="h-6 w-6 my-1"
+        />
     };
 
     if block {
@@ -430,17 +856,25 @@ fn to_fraction(ratio: Ratio<BigUint>) -> BigFraction {
 #[component]
 pub fn ResultDisplay(
     running: ReadSignal<bool>,
-    result: ReadSignal<Option<SolverResult>>,
+    result: ReadSignal<
+        Option<Result<SolverResult, WorkerError>>,
+    >,
+    progress: ReadSignal<Option<Partial>>,
     precision: RwSignal<usize>,
     label: &'static str,
 ) -> impl IntoView {
+    let solved = move || {
+        result
+            .get()
+            .and_then(Result::ok)
+            .unwrap_or_default()
+    };
+
     view! {
         <div>
             <div class="border-b-2 border-black flex justify-between items-center">
-                <p>
-                    Took:
-                    {move || format!("{}", result.get().unwrap_or_default().took)}
-                </p>
+                <p>Took: {move || format!("{}", solved().took)}</p>
+                <p>Iterations: {move || solved().iterations}</p>
                 <p>{label}</p>
                 <p
                     class="bg-red-500 rounded px-[2px] py-px"
@@ -449,17 +883,919 @@ pub fn ResultDisplay(
                     {move || running.get().then_some("Running").unwrap_or("Idling")}
                 </p>
             </div>
-            <p class="break-words">
-                {move || {
-                    let precision = precision.get() as usize;
-                    scientific_notation(result.get().unwrap_or_default().probability, precision)
-                }}
+            {move || {
+                running
+                    .get()
+                    .then(|| progress.get())
+                    .flatten()
+                    .map(|partial| {
+                        view! {
+                            <div class="border-b-2 border-black text-sm">
+                                <div class="flex items-center gap-2">
+                                    <p>{partial.stage.to_string()} {partial.iteration}</p>
+                                    {partial
+                                        .total
+                                        .map(|total| {
+                                            view! {
+                                                <progress
+                                                    class="grow"
+                                                    max=total
+                                                    value=partial.iteration
+                                                ></progress>
+                                            }
+                                        })}
+
+                                </div>
+                                {partial
+                                    .probability
+                                    .map(|probability| {
+                                        view! {
+                                            <p class="break-words text-gray-600">
+                                                {scientific_notation(probability, precision.get())}
+                                            </p>
+                                        }
+                                    })}
+
+                            </div>
+                        }
+                    })
+            }}
+
+            {move || match result.get() {
+                Some(Err(error)) => {
+                    view! { <p class="break-words text-red-600">{error.to_string()}</p> }
+                }
+                _ => {
+                    view! {
+                        <p class="break-words">
+                            {move || {
+                                let precision = precision.get() as usize;
+                                scientific_notation(solved().probability, precision)
+                            }}
+
+                        </p>
+                    }
+                }
+            }}
+
+            {move || {
+                solved()
+                    .bounds
+                    .map(|bounds| {
+                        let accuracy = match bounds.accurate_digits() {
+                            Some(digits) => format!("Accurate to {} digits", digits),
+                            None => "Exact".into(),
+                        };
+                        view! { <p class="text-sm text-gray-600">{accuracy}</p> }
+                    })
+            }}
+        </div>
+    }
+}
+
+#[component]
+pub fn SweepView(
+    variables: Variables,
+    panel: JobPanel<Vec<SweepPoint>>,
+    next_id: StoredValue<u64>,
+) -> impl IntoView {
+    let experiments_from = create_rw_signal(10u32);
+    let experiments_to = create_rw_signal(200u32);
+    let experiments_step = create_rw_signal(10u32);
+    let numerator_from = create_rw_signal(80u32);
+    let numerator_to = create_rw_signal(80u32);
+    let numerator_step = create_rw_signal(1u32);
+
+    let start = move |_| {
+        let request = variables.request();
+        let sweep = Sweep {
+            experiments: Steps {
+                from: experiments_from.get_untracked(),
+                to: experiments_to.get_untracked(),
+                step: experiments_step.get_untracked(),
+            },
+            outcomes: Outcomes::Mean,
+            numerators: Steps {
+                from: numerator_from.get_untracked(),
+                to: numerator_to.get_untracked(),
+                step: numerator_step.get_untracked(),
+            },
+            denominator: variables
+                .denominator
+                .get_untracked(),
+            iterations: request.exponent_iterations(),
+            square_root: request.square_root(),
+            pi_digits: request.precision,
+        };
+
+        let id = next_id.get_value();
+        next_id.set_value(id + 1);
+        panel.send(id, ToWorker::Sweep(sweep));
+    };
+
+    view! {
+        <div class="border-2 border-black rounded p-2 mx-2 mb-2">
+            <p class="border-b-2 border-black">
+                "Sweep: error of Moivre Laplace at k closest to np"
+            </p>
+            <div class="flex flex-wrap justify-around child:px-2">
+                <Variable value=experiments_from id="sweep_n_from" label="n From" block=true/>
+                <Variable value=experiments_to id="sweep_n_to" label="n To" block=true/>
+                <Variable value=experiments_step id="sweep_n_step" label="n Step" block=true/>
+                <Variable
+                    value=numerator_from
+                    id="sweep_p_from"
+                    label="p Numerator From"
+                    tooltip="Over the denominator of p above"
+                    block=true
+                />
+                <Variable value=numerator_to id="sweep_p_to" label="p Numerator To" block=true/>
+                <Variable
+                    value=numerator_step
+                    id="sweep_p_step"
+                    label="p Numerator Step"
+                    block=true
+                />
+            </div>
+            <div class="flex gap-2">
+                <button on:click=start class="bg-blue-500 rounded mt-2 h-10 text-2xl grow">
+                    Sweep
+                </button>
+                <button
+                    on:click=move |_| panel.cancel()
+                    disabled=move || !panel.running.get()
+                    class="bg-red-500 disabled:bg-gray-300 rounded mt-2 h-10 text-2xl grow"
+                >
+                    Cancel
+                </button>
+            </div>
+            {move || {
+                panel
+                    .running
+                    .get()
+                    .then(|| panel.progress.get())
+                    .flatten()
+                    .and_then(|partial| {
+                        partial
+                            .total
+                            .map(|total| {
+                                view! {
+                                    <progress
+                                        class="w-full"
+                                        max=total
+                                        value=partial.iteration
+                                    ></progress>
+                                }
+                            })
+                    })
+            }}
+
+            {move || match panel.output.get() {
+                Some(Ok(points)) => view! { <SweepChart points/> }.into_view(),
+                Some(Err(error)) => {
+                    view! { <p class="break-words text-red-600">{error.to_string()}</p> }
+                        .into_view()
+                }
+                None => ().into_view(),
+            }}
+        </div>
+    }
+}
+
+// Correct digits of the approximation, -log10 of the relative
+// error, against n with one line per p, or against p with one
+// line per n if n stays the same
+#[component]
+pub fn SweepChart(
+    points: Vec<SweepPoint>,
+) -> impl IntoView {
+    const WIDTH: f64 = 600.0;
+    const HEIGHT: f64 = 300.0;
+    const MARGIN: f64 = 40.0;
+
+    let over_experiments = points
+        .first()
+        .map(|first| {
+            points.iter().any(|point| {
+                point.experiments != first.experiments
+            })
+        })
+        .unwrap_or(true);
+
+    // One series per value of the parameter that stays fixed
+    // along the x axis. With several k per n and p, the i-th k
+    // of every n and p goes into the same series
+    let mut taken: Vec<(u32, String)> = Vec::new();
+    let mut series: Vec<(String, Vec<(f64, f64)>)> =
+        Vec::new();
+    for point in &points {
+        let probability = format!("{}", point.probability);
+        let ordinal = taken
+            .iter()
+            .filter(|(experiments, p)| {
+                *experiments == point.experiments
+                    && *p == probability
+            })
+            .count();
+        taken
+            .push((point.experiments, probability.clone()));
+
+        let mut label = if over_experiments {
+            format!("p = {}", probability)
+        } else {
+            format!("n = {}", point.experiments)
+        };
+        if ordinal > 0 {
+            label += &format!(" #{}", ordinal + 1);
+        }
+        let index = match series
+            .iter()
+            .position(|(existing, _)| *existing == label)
+        {
+            Some(index) => index,
+            None => {
+                series.push((label, Vec::new()));
+                series.len() - 1
+            }
+        };
+
+        let x = if over_experiments {
+            point.experiments as f64
+        } else {
+            10f64.powf(log10(&point.probability))
+        };
+        let Some(error) = point.relative_error.as_ref()
+        else {
+            continue;
+        };
+        let y = -log10(error);
+        if y.is_finite() {
+            series[index].1.push((x, y));
+        }
+    }
+    series.retain(|(_, values)| !values.is_empty());
+    for (_, values) in &mut series {
+        values.sort_by(|a, b| a.0.total_cmp(&b.0));
+    }
+    let values: Vec<(f64, f64)> = series
+        .iter()
+        .flat_map(|(_, values)| values.iter().copied())
+        .collect();
 
+    if values.is_empty() {
+        return view! { <p>"No points with a non zero error"</p> }.into_view();
+    }
+
+    let bounds = |values: &mut dyn Iterator<Item = f64>| {
+        values.fold(
+            (f64::INFINITY, f64::NEG_INFINITY),
+            |(min, max), value| {
+                (min.min(value), max.max(value))
+            },
+        )
+    };
+    let (x_min, x_max) =
+        bounds(&mut values.iter().map(|(x, _)| *x));
+    let (y_min, y_max) =
+        bounds(&mut values.iter().map(|(_, y)| *y));
+    let (y_min, y_max) =
+        (y_min.min(0.0).floor(), y_max.ceil().max(1.0));
+
+    // A single point sits in the middle instead of dividing by zero
+    let scale_x = move |x: f64| {
+        if x_max > x_min {
+            MARGIN
+                + (x - x_min) / (x_max - x_min)
+                    * (WIDTH - 2.0 * MARGIN)
+        } else {
+            WIDTH / 2.0
+        }
+    };
+    let scale_y = move |y: f64| {
+        HEIGHT
+            - MARGIN
+            - (y - y_min) / (y_max - y_min)
+                * (HEIGHT - 2.0 * MARGIN)
+    };
+
+    const COLORS: [&str; 6] = [
+        "blue", "red", "green", "orange", "purple", "teal",
+    ];
+    let x_label = if over_experiments { "n" } else { "p" };
+
+    view! {
+        <svg viewBox=format!("0 0 {} {}", WIDTH, HEIGHT) class="w-full max-h-96">
+            <line
+                x1=MARGIN
+                y1=HEIGHT - MARGIN
+                x2=WIDTH - MARGIN
+                y2=HEIGHT - MARGIN
+                stroke="black"
+            ></line>
+            <line x1=MARGIN y1=MARGIN x2=MARGIN y2=HEIGHT - MARGIN stroke="black"></line>
+            <text x=MARGIN y=HEIGHT - MARGIN / 2.0 text-anchor="middle">
+                {format!("{}", x_min)}
+            </text>
+            <text x=WIDTH - MARGIN y=HEIGHT - MARGIN / 2.0 text-anchor="middle">
+                {format!("{}", x_max)}
+            </text>
+            <text x=WIDTH / 2.0 y=HEIGHT - 5.0 text-anchor="middle">
+                {x_label}
+            </text>
+            <text x=MARGIN - 5.0 y=HEIGHT - MARGIN text-anchor="end">
+                {format!("{}", y_min)}
+            </text>
+            <text x=MARGIN - 5.0 y=MARGIN text-anchor="end">
+                {format!("{}", y_max)}
+            </text>
+            <text x=MARGIN y=MARGIN / 2.0>
+                "Correct digits, -log10(relative error)"
+            </text>
+            {series
+                .into_iter()
+                .enumerate()
+                .map(|(index, (label, values))| {
+                    let color = COLORS[index % COLORS.len()];
+                    let line = values
+                        .iter()
+                        .map(|(x, y)| format!("{},{}", scale_x(*x), scale_y(*y)))
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    view! {
+                        <polyline points=line fill="none" stroke=color stroke-width="2">
+                            <title>{label.clone()}</title>
+                        </polyline>
+                        {values
+                            .into_iter()
+                            .map(|(x, y)| {
+                                view! {
+                                    <circle cx=scale_x(x) cy=scale_y(y) r="3" fill=color>
+                                        <title>
+                                            {format!("{}, {} = {}: {:.2} digits", label, x_label, x, y)}
+                                        </title>
+                                    </circle>
+                                }
+                            })
+                            .collect_view()}
+                    }
+                })
+                .collect_view()}
+        </svg>
+    }
+    .into_view()
+}
+
+// Guarantees that only depend on n and p, next to the error
+// the last Calculate actually made
+#[component]
+pub fn ErrorBoundsView(
+    variables: Variables,
+    exact: ReadSignal<
+        Option<Result<SolverResult, WorkerError>>,
+    >,
+    approximation: ReadSignal<
+        Option<Result<SolverResult, WorkerError>>,
+    >,
+) -> impl IntoView {
+    // Only for display, so f64 is plenty
+    let approximate =
+        |fraction: &GenericFraction<BigUint>| {
+            format!("{:.4e}", 10f64.powf(log10(fraction)))
+        };
+
+    let bounds = move || {
+        error_bounds(
+            variables.total_experiments.get(),
+            Ratio::new_raw(
+                variables.pass_numerator.get().into(),
+                variables.denominator.get().into(),
+            ),
+            20,
+            &Cancellation::new(),
+        )
+    };
+
+    // |exact - approximation| along with the bound it has to stay
+    // under, if there is one
+    let measured = move || {
+        let (Some(Ok(exact)), Some(Ok(approximation))) =
+            (exact.get(), approximation.get())
+        else {
+            return None;
+        };
+        let error = exact.probability.clone()
+            - approximation.probability;
+        let relative = 10f64.powf(
+            log10(&error) - log10(&exact.probability),
+        );
+        Some((error, relative))
+    };
+
+    view! {
+        <div class="border-2 border-black rounded p-2 mx-2 mb-2">
+            <p class="border-b-2 border-black">"A priori error bounds"</p>
+            {move || match bounds() {
+                Ok(bounds) => {
+                    let holds = measured()
+                        .filter(|_| variables.interval.get())
+                        .map(|(error, _)| {
+                            let error = 10f64.powf(log10(&error));
+                            error <= 10f64.powf(log10(&bounds.interval.upper))
+                        });
+                    view! {
+                        <p>
+                            "Berry Esseen, sup |F(x) - Φ(x)| ≤ "
+                            {approximate(&bounds.berry_esseen.upper)}
+                        </p>
+                        <p>
+                            "Integral theorem, |P(a ≤ X ≤ b) - (Φ(b) - Φ(a))| ≤ "
+                            {approximate(&bounds.interval.upper)}
+                            {holds
+                                .map(|holds| {
+                                    if holds { " (holds)" } else { " (violated)" }
+                                })}
+                        </p>
+                        <p>
+                            "Local theorem, relative error of order 1/√npq = "
+                            {approximate(&bounds.local_order.upper)}
+                        </p>
+                    }
+                        .into_view()
+                }
+                Err(error) => {
+                    view! { <p class="break-words text-red-600">{error.to_string()}</p> }
+                        .into_view()
+                }
+            }}
+            {move || {
+                measured()
+                    .map(|(error, relative)| {
+                        view! {
+                            <p>
+                                "Measured |Bernoulli - Moivre Laplace| = "
+                                {approximate(&error)} ", relative " {format!("{:.4e}", relative)}
+                            </p>
+                        }
+                    })
+            }}
+        </div>
+    }
+}
+
+#[component]
+pub fn DistributionView(
+    variables: Variables,
+    panel: JobPanel<Distribution>,
+) -> impl IntoView {
+    view! {
+        <div class="border-2 border-black rounded p-2 mx-2 mb-2">
+            <p class="border-b-2 border-black">
+                "Distribution: exact P(X = k) against the Moivre Laplace curve"
             </p>
+            {move || {
+                panel
+                    .running
+                    .get()
+                    .then(|| panel.progress.get())
+                    .flatten()
+                    .and_then(|partial| {
+                        partial
+                            .total
+                            .map(|total| {
+                                view! {
+                                    <progress
+                                        class="w-full"
+                                        max=total
+                                        value=partial.iteration
+                                    ></progress>
+                                }
+                            })
+                    })
+            }}
+
+            {move || match panel.output.get() {
+                Some(Ok(distribution)) => {
+                    // The sum is exact, anything but 1 is a bug in the recurrence. Values
+                    // cut off around the mode don't sum to anything known
+                    let consistent = !distribution.is_complete() || distribution.sum.is_one();
+                    view! {
+                        <div class="flex justify-between">
+                            <p>Took: {format!("{}", distribution.took)}</p>
+                            <p class=("text-red-600", !consistent)>
+                                Sum: {format!("{}", distribution.sum)}
+                            </p>
+                        </div>
+                        <DistributionChart
+                            mean=distribution.mean()
+                            variance=distribution.variance()
+                            values=distribution.values
+                            first=distribution.first
+                            variables
+                        />
+                    }
+                        .into_view()
+                }
+                Some(Err(error)) => {
+                    view! { <p class="break-words text-red-600">{error.to_string()}</p> }
+                        .into_view()
+                }
+                None => ().into_view(),
+            }}
+        </div>
+    }
+}
+
+// Further than this many standard deviations from the mean the
+// bars are too small to see, so the worker doesn't compute them
+const CHART_DEVIATIONS: u32 = 5;
+
+// Bars of P(X = k) for k from first on, with the density
+// phi((k - np) / sqrt(npq)) / sqrt(npq) drawn over them. The k
+// that are asked for in the variables are highlighted
+#[component]
+pub fn DistributionChart(
+    mean: GenericFraction<BigUint>,
+    variance: GenericFraction<BigUint>,
+    values: Vec<GenericFraction<BigUint>>,
+    first: u32,
+    variables: Variables,
+) -> impl IntoView {
+    const WIDTH: f64 = 600.0;
+    const HEIGHT: f64 = 300.0;
+    const MARGIN: f64 = 40.0;
+
+    let heights: Vec<f64> = values
+        .iter()
+        .map(|value| 10f64.powf(log10(value)))
+        .collect();
+
+    // np and npq of the distribution rather than of the bars,
+    // which may be cut off
+    let first = first as usize;
+    let mean = 10f64.powf(log10(&mean));
+    let variance = 10f64.powf(log10(&variance));
+    let deviation = variance.sqrt();
+    let density = move |x: f64| {
+        (-((x - mean) / deviation).powi(2) / 2.0).exp()
+            / (deviation
+                * (2.0 * std::f64::consts::PI).sqrt())
+    };
+
+    let shown = CHART_DEVIATIONS as f64 * deviation;
+    let last = first + heights.len().saturating_sub(1);
+    let from = ((mean - shown).floor().max(0.0) as usize)
+        .clamp(first, last);
+    let to =
+        ((mean + shown).ceil() as usize).clamp(from, last);
+
+    let y_max = heights[from - first..=to - first]
+        .iter()
+        .copied()
+        .fold(density(mean), f64::max);
+    let bar_width =
+        (WIDTH - 2.0 * MARGIN) / (to - from + 1) as f64;
+    let scale_x = move |x: f64| {
+        MARGIN + (x - from as f64 + 0.5) * bar_width
+    };
+    let scale_y = move |y: f64| {
+        HEIGHT
+            - MARGIN
+            - y / y_max * (HEIGHT - 2.0 * MARGIN)
+    };
+
+    // p = 0 or p = 1 leaves a single bar and no curve
+    let samples = 200;
+    let curve = (deviation > 0.0)
+        .then(|| {
+            (0..=samples)
+                .map(|sample| {
+                    let x = from as f64 - 0.5
+                        + (to - from + 1) as f64
+                            * sample as f64
+                            / samples as f64;
+                    format!(
+                        "{},{}",
+                        scale_x(x),
+                        scale_y(density(x))
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .unwrap_or_default();
+
+    let selected = move |k: u32| {
+        let required = variables.required_to_pass.get();
+        if variables.interval.get() {
+            (required
+                ..=variables.required_to_pass_max.get())
+                .contains(&k)
+        } else {
+            k == required
+        }
+    };
+
+    view! {
+        <svg viewBox=format!("0 0 {} {}", WIDTH, HEIGHT) class="w-full max-h-96">
+            <line
+                x1=MARGIN
+                y1=HEIGHT - MARGIN
+                x2=WIDTH - MARGIN
+                y2=HEIGHT - MARGIN
+                stroke="black"
+            ></line>
+            <text x=MARGIN y=HEIGHT - MARGIN / 2.0 text-anchor="middle">
+                {from}
+            </text>
+            <text x=WIDTH - MARGIN y=HEIGHT - MARGIN / 2.0 text-anchor="middle">
+                {to}
+            </text>
+            <text x=WIDTH / 2.0 y=HEIGHT - 5.0 text-anchor="middle">
+                "k"
+            </text>
+            <text x=MARGIN y=MARGIN / 2.0>
+                {format!("max {:.3e}", y_max)}
+            </text>
+            {values[from - first..=to - first]
+                .iter()
+                .zip(&heights[from - first..=to - first])
+                .enumerate()
+                .map(|(index, (value, height))| {
+                    let k = (from + index) as u32;
+                    let title = format!(
+                        "k = {}\nP(X = k) = {}\nMoivre Laplace = {:.6e}",
+                        k,
+                        scientific_notation(value.clone(), 20),
+                        density(k as f64),
+                    );
+                    view! {
+                        <rect
+                            x=scale_x(k as f64 - 0.5)
+                            y=scale_y(*height)
+                            width=bar_width
+                            height=HEIGHT - MARGIN - scale_y(*height)
+                            fill=move || if selected(k) { "orange" } else { "lightsteelblue" }
+                            stroke="white"
+                        >
+                            <title>{title}</title>
+                        </rect>
+                    }
+                })
+                .collect_view()}
+            <polyline points=curve fill="none" stroke="red" stroke-width="2"></polyline>
+        </svg>
+    }
+}
+
+#[component]
+pub fn HeatmapView(
+    variables: Variables,
+    panel: JobPanel<Vec<SweepPoint>>,
+    next_id: StoredValue<u64>,
+) -> impl IntoView {
+    let experiments_from = create_rw_signal(10u32);
+    let experiments_to = create_rw_signal(100u32);
+    let experiments_step = create_rw_signal(10u32);
+    let numerator_from = create_rw_signal(10u32);
+    let numerator_to = create_rw_signal(90u32);
+    let numerator_step = create_rw_signal(10u32);
+    let offset = create_rw_signal(0f64);
+
+    let start = move |_| {
+        let request = variables.request();
+        let grid = Sweep {
+            experiments: Steps {
+                from: experiments_from.get_untracked(),
+                to: experiments_to.get_untracked(),
+                step: experiments_step.get_untracked(),
+            },
+            outcomes: Outcomes::Deviations(
+                offset.get_untracked(),
+            ),
+            numerators: Steps {
+                from: numerator_from.get_untracked(),
+                to: numerator_to.get_untracked(),
+                step: numerator_step.get_untracked(),
+            },
+            denominator: variables
+                .denominator
+                .get_untracked(),
+            iterations: request.exponent_iterations(),
+            square_root: request.square_root(),
+            pi_digits: request.precision,
+        };
+
+        let id = next_id.get_value();
+        next_id.set_value(id + 1);
+        panel.send(id, ToWorker::Sweep(grid));
+    };
+
+    view! {
+        <div class="border-2 border-black rounded p-2 mx-2 mb-2">
+            <p class="border-b-2 border-black">
+                "Heatmap: relative error of Moivre Laplace over n and p"
+            </p>
+            <div class="flex flex-wrap justify-around child:px-2">
+                <Variable value=experiments_from id="heatmap_n_from" label="n From" block=true/>
+                <Variable value=experiments_to id="heatmap_n_to" label="n To" block=true/>
+                <Variable value=experiments_step id="heatmap_n_step" label="n Step" block=true/>
+                <Variable
+                    value=numerator_from
+                    id="heatmap_p_from"
+                    label="p Numerator From"
+                    tooltip="Over the denominator of p above"
+                    block=true
+                />
+                <Variable value=numerator_to id="heatmap_p_to" label="p Numerator To" block=true/>
+                <Variable
+                    value=numerator_step
+                    id="heatmap_p_step"
+                    label="p Numerator Step"
+                    block=true
+                />
+                <Variable
+                    value=offset
+                    id="heatmap_offset"
+                    label="k Offset"
+                    tooltip="k is the mode moved by this many standard deviations"
+                    block=true
+                />
+            </div>
+            <div class="flex gap-2">
+                <button on:click=start class="bg-blue-500 rounded mt-2 h-10 text-2xl grow">
+                    Compute
+                </button>
+                <button
+                    on:click=move |_| panel.cancel()
+                    disabled=move || !panel.running.get()
+                    class="bg-red-500 disabled:bg-gray-300 rounded mt-2 h-10 text-2xl grow"
+                >
+                    Cancel
+                </button>
+            </div>
+            {move || {
+                panel
+                    .running
+                    .get()
+                    .then(|| panel.progress.get())
+                    .flatten()
+                    .and_then(|partial| {
+                        partial
+                            .total
+                            .map(|total| {
+                                view! {
+                                    <progress
+                                        class="w-full"
+                                        max=total
+                                        value=partial.iteration
+                                    ></progress>
+                                }
+                            })
+                    })
+            }}
+
+            {move || match panel.output.get() {
+                Some(Ok(points)) => view! { <HeatmapChart points/> }.into_view(),
+                Some(Err(error)) => {
+                    view! { <p class="break-words text-red-600">{error.to_string()}</p> }
+                        .into_view()
+                }
+                None => ().into_view(),
+            }}
         </div>
     }
 }
 
+// One cell per n and p, going from red where the approximation
+// gets no digit right to green for the most correct digits of
+// the grid. Cells where the exact value is zero are grey
+#[component]
+pub fn HeatmapChart(
+    points: Vec<SweepPoint>,
+) -> impl IntoView {
+    const WIDTH: f64 = 600.0;
+    const HEIGHT: f64 = 400.0;
+    const MARGIN: f64 = 50.0;
+
+    let mut rows: Vec<u32> = points
+        .iter()
+        .map(|point| point.experiments)
+        .collect();
+    rows.sort();
+    rows.dedup();
+    let mut columns: Vec<GenericFraction<BigUint>> = points
+        .iter()
+        .map(|point| point.probability.clone())
+        .collect();
+    columns.sort();
+    columns.dedup();
+
+    if rows.is_empty() || columns.is_empty() {
+        return view! { <p>"No points, p has to be strictly between 0 and 1"</p> }
+            .into_view();
+    }
+
+    let digits = |point: &SweepPoint| {
+        point
+            .relative_error
+            .as_ref()
+            .map(|error| -log10(error))
+    };
+    let most = points
+        .iter()
+        .filter_map(digits)
+        .filter(|digits| digits.is_finite())
+        .fold(1.0, f64::max);
+
+    let cell_width =
+        (WIDTH - MARGIN) / columns.len() as f64;
+    let cell_height = (HEIGHT - MARGIN) / rows.len() as f64;
+    let float = |fraction: &GenericFraction<BigUint>| {
+        10f64.powf(log10(fraction))
+    };
+
+    view! {
+        <svg viewBox=format!("0 0 {} {}", WIDTH, HEIGHT) class="w-full max-h-[32rem]">
+            {rows
+                .iter()
+                .enumerate()
+                .map(|(row, experiments)| {
+                    view! {
+                        <text
+                            x=MARGIN - 5.0
+                            y=MARGIN + (row as f64 + 0.5) * cell_height
+                            text-anchor="end"
+                            dominant-baseline="middle"
+                            font-size="12"
+                        >
+                            {*experiments}
+                        </text>
+                    }
+                })
+                .collect_view()}
+            {columns
+                .iter()
+                .enumerate()
+                .map(|(column, p)| {
+                    view! {
+                        <text
+                            x=MARGIN + (column as f64 + 0.5) * cell_width
+                            y=MARGIN - 5.0
+                            text-anchor="middle"
+                            font-size="12"
+                        >
+                            {format!("{:.3}", float(p))}
+                        </text>
+                    }
+                })
+                .collect_view()}
+            <text x=5.0 y=15.0 font-size="12">
+                {format!("n down, p across, green is {:.1} correct digits", most)}
+            </text>
+            {points
+                .iter()
+                .map(|point| {
+                    let row = rows.binary_search(&point.experiments).unwrap_or_default();
+                    let column = columns.binary_search(&point.probability).unwrap_or_default();
+                    let digits = digits(point);
+                    let fill = match digits {
+                        Some(digits) if digits.is_finite() => {
+                            let hue = 120.0 * (digits / most).clamp(0.0, 1.0);
+                            format!("hsl({}, 70%, 50%)", hue)
+                        }
+                        _ => "lightgray".to_string(),
+                    };
+                    let title = format!(
+                        "n = {}, p = {}, k = {}\nRelative error = {}\nCorrect digits = {}",
+                        point.experiments,
+                        float(&point.probability),
+                        point.outcomes,
+                        point
+                            .relative_error
+                            .as_ref()
+                            .map(|error| format!("{:.3e}", float(error)))
+                            .unwrap_or_else(|| "undefined".into()),
+                        digits.map(|digits| format!("{:.2}", digits)).unwrap_or_default(),
+                    );
+                    view! {
+                        <rect
+                            x=MARGIN + column as f64 * cell_width
+                            y=MARGIN + row as f64 * cell_height
+                            width=cell_width
+                            height=cell_height
+                            fill=fill
+                            stroke="white"
+                        >
+                            <title>{title}</title>
+                        </rect>
+                    }
+                })
+                .collect_view()}
+        </svg>
+    }
+    .into_view()
+}
+
 fn main() {
     console_error_panic_hook::set_once();
 
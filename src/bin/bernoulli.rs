@@ -1,6 +0,0 @@
-use gloo_worker::Registrable;
-use bernoulli_vs_moivre_laplace::{BernoulliSolver};
-
-fn main() {
-    BernoulliSolver::registrar().register();
-}
@@ -0,0 +1,3 @@
+fn main() {
+    bernoulli_vs_moivre_laplace::register();
+}
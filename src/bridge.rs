@@ -0,0 +1,184 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use js_sys::{Array, Uint8Array};
+use serde::Serialize;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{
+    Blob, BlobPropertyBag, DedicatedWorkerGlobalScope,
+    MessageEvent, Url, Worker,
+};
+
+use crate::{
+    decode, encode, handle, Envelope, FromWorker, ToWorker,
+};
+
+// First message of a worker, sent once it listens for
+// requests. Anything posted before that would get lost. Every
+// other message is an envelope encoded with bincode
+const LOADED: &str = "loaded";
+
+// One worker running the solver, the app spawns one instance
+// per method so that they run in parallel. A job runs
+// synchronously inside the worker, so the only way to stop it
+// is to terminate the worker, which dropping this does
+pub struct SolverWorker {
+    worker: Worker,
+    // Requests sent before the worker loaded, None after
+    pending: Rc<RefCell<Option<Vec<JsValue>>>>,
+    // Kept alive for as long as the worker can call it
+    _on_message: Closure<dyn Fn(MessageEvent)>,
+}
+
+impl SolverWorker {
+    // path is the script trunk builds for the worker, which
+    // only defines wasm_bindgen, so a small loader imports it
+    // and starts the wasm next to it. Fails for a path that
+    // isn't a url to a .js file
+    pub fn spawn(
+        path: &str,
+        callback: impl Fn(Envelope<FromWorker>) + 'static,
+    ) -> Result<Self, String> {
+        let href = web_sys::window()
+            .and_then(|window| {
+                window.location().href().ok()
+            })
+            .expect("the app runs in a window");
+        let script = Url::new_with_base(path, &href)
+            .map_err(|_| {
+                format!("{} is not a valid url", path)
+            })?
+            .href();
+        // Only the extension, a .js elsewhere in the url is
+        // part of a directory
+        let wasm = match script.strip_suffix(".js") {
+            Some(stem) => format!("{}_bg.wasm", stem),
+            None => {
+                return Err(format!(
+                    "{} is not a .js file",
+                    path
+                ))
+            }
+        };
+        let loader = Array::of1(
+            &format!(
+                r#"importScripts("{script}");wasm_bindgen("{wasm}");"#
+            )
+            .into(),
+        );
+        let blob = Blob::new_with_str_sequence_and_options(
+            &loader,
+            BlobPropertyBag::new()
+                .type_("application/javascript"),
+        )
+        .expect("the loader is a valid blob");
+        let url = Url::create_object_url_with_blob(&blob)
+            .expect("blobs always get a url");
+        let worker = Worker::new(&url)
+            .expect("failed to spawn the worker");
+
+        let pending: Rc<RefCell<Option<Vec<JsValue>>>> =
+            Rc::new(RefCell::new(Some(Vec::new())));
+        let on_message = {
+            let worker = worker.clone();
+            let pending = pending.clone();
+            Closure::<dyn Fn(MessageEvent)>::new(
+                move |event: MessageEvent| {
+                    let data = event.data();
+                    if data.as_string().as_deref()
+                        == Some(LOADED)
+                    {
+                        let queued = pending.take();
+                        for message in
+                            queued.into_iter().flatten()
+                        {
+                            post(&worker, &message);
+                        }
+                        return;
+                    }
+                    callback(
+                        decode(&bytes(&data))
+                            .unwrap_or_else(|error| {
+                                Envelope::new(
+                                    error.id,
+                                    FromWorker::Failed(
+                                        error.message,
+                                    ),
+                                )
+                            }),
+                    )
+                },
+            )
+        };
+        worker.set_onmessage(Some(
+            on_message.as_ref().unchecked_ref(),
+        ));
+
+        Ok(Self {
+            worker,
+            pending,
+            _on_message: on_message,
+        })
+    }
+
+    pub fn send(&self, envelope: Envelope<ToWorker>) {
+        let message = message(&envelope);
+        match self.pending.borrow_mut().as_mut() {
+            Some(pending) => pending.push(message),
+            None => post(&self.worker, &message),
+        }
+    }
+}
+
+impl Drop for SolverWorker {
+    fn drop(&mut self) {
+        // Responses already on their way would otherwise call
+        // the closure after it is freed
+        self.worker.set_onmessage(None);
+        self.worker.terminate();
+    }
+}
+
+fn post(worker: &Worker, message: &JsValue) {
+    worker
+        .post_message(message)
+        .expect("bytes can always be posted");
+}
+
+fn message<T: Serialize>(
+    envelope: &Envelope<T>,
+) -> JsValue {
+    Uint8Array::from(encode(envelope).as_slice()).into()
+}
+
+fn bytes(data: &JsValue) -> Vec<u8> {
+    Uint8Array::new(data).to_vec()
+}
+
+// Entry point of the worker, answers every request with handle
+pub fn register() {
+    let scope: DedicatedWorkerGlobalScope =
+        js_sys::global().unchecked_into();
+    let respond = {
+        let scope = scope.clone();
+        move |envelope: Envelope<FromWorker>| {
+            scope
+                .post_message(&message(&envelope))
+                .expect("bytes can always be posted");
+        }
+    };
+    let on_message = Closure::<dyn Fn(MessageEvent)>::new(
+        move |event: MessageEvent| {
+            handle(&bytes(&event.data()), &respond)
+        },
+    );
+    scope.set_onmessage(Some(
+        on_message.as_ref().unchecked_ref(),
+    ));
+    // The handler is needed for as long as the worker lives
+    on_message.forget();
+    scope
+        .post_message(&JsValue::from_str(LOADED))
+        .expect("strings can always be posted");
+}
@@ -1,91 +1,359 @@
-use gloo_worker::Worker;
 use num_bigint::BigUint;
 use num_rational::Ratio;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use solver::{bernoulli, moivre_laplace, SolverResult};
+use solver::{
+    distribution, sweep, Cancellation, Distribution,
+    Iterations, Partial, Query, Settings, SolverError,
+    SolverResult, SquareRoot, Start, Sweep, SweepPoint,
+};
 
-pub struct BernoulliSolver;
-pub struct MoivreLaplaceSolver;
+mod bridge;
+
+pub use bridge::{register, SolverWorker};
+
+// Bumped whenever a message changes shape, so an app and a
+// worker built from different versions fail loudly instead of
+// misreading each other
+pub const PROTOCOL_VERSION: u32 = 1;
+
+// Shared with the command line, so that both dispatch the
+// methods the same way
+pub use solver::Method;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SolverRequest {
     pub total: u32,
     pub required: u32,
+    // When set, asks for P(from <= X <= to) instead of P(X = required)
+    pub interval: Option<(u32, u32)>,
     pub odds: Ratio<BigUint>,
     pub iterations: usize,
+    pub automatic_iterations: bool,
+    pub reduce_exponent: bool,
     pub stable_amount: usize,
     pub precision: usize,
     pub sqrt_iterations: usize,
+    pub sqrt_to_digits: bool,
+    pub sqrt_digits: usize,
+    // Order of the Edgeworth correction, see solver::edgeworth
+    pub edgeworth_order: u32,
+    // Corrections of the Stirling series, see solver::stirling
+    pub stirling_terms: usize,
 }
 
-impl Worker for BernoulliSolver {
-    type Message = ();
+// Every message in either direction is wrapped in this. The id
+// is picked by the app and copied onto every response to the
+// request, so responses to older requests can be told apart
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Envelope<T> {
+    pub version: u32,
+    pub id: u64,
+    pub message: T,
+}
 
-    type Input = SolverRequest;
+impl<T> Envelope<T> {
+    pub fn new(id: u64, message: T) -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            id,
+            message,
+        }
+    }
+}
 
-    type Output = SolverResult;
+// Leading fields of every envelope. They are read on their own
+// first, since the message of another version might not decode
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+    id: u64,
+}
 
-    fn create(
-        _scope: &gloo_worker::WorkerScope<Self>,
-    ) -> Self {
-        Self {}
-    }
+// Both ends send envelopes encoded with bincode
+pub fn encode<T: Serialize>(
+    envelope: &Envelope<T>,
+) -> Vec<u8> {
+    bincode::serialize(envelope)
+        .expect("messages only hold serializable data")
+}
 
-    fn update(
-        &mut self,
-        _scope: &gloo_worker::WorkerScope<Self>,
-        _msg: Self::Message,
-    ) {
+// An envelope from another version of the protocol comes back
+// as the error to answer it with, under its id if it has one
+pub fn decode<T: DeserializeOwned>(
+    bytes: &[u8],
+) -> Result<Envelope<T>, Envelope<WorkerError>> {
+    let Ok(header) = bincode::deserialize::<Header>(bytes)
+    else {
+        return Err(Envelope::new(
+            0,
+            WorkerError::Malformed,
+        ));
+    };
+    if header.version != PROTOCOL_VERSION {
+        return Err(Envelope::new(
+            header.id,
+            WorkerError::VersionMismatch {
+                expected: PROTOCOL_VERSION,
+                received: header.version,
+            },
+        ));
     }
+    bincode::deserialize(bytes).map_err(|_| {
+        Envelope::new(header.id, WorkerError::Malformed)
+    })
+}
 
-    fn received(
-        &mut self,
-        scope: &gloo_worker::WorkerScope<Self>,
-        msg: Self::Input,
-        id: gloo_worker::HandlerId,
-    ) {
-        scope.respond(
-            id,
-            bernoulli(msg.total, msg.required, msg.odds),
-        )
+#[derive(Serialize, Deserialize, Clone)]
+pub enum ToWorker {
+    Compute(Method, SolverRequest),
+    Sweep(Sweep),
+    // P(X = k) for n experiments with the probability p, see
+    // solver::distribution for the standard deviations
+    Distribution(u32, Ratio<BigUint>, Start, Option<u32>),
+    // Cancels the job sent with this id
+    Cancel { id: u64 },
+}
+
+// Any amount of Progress while a request is running, then
+// exactly one of Done, Swept, Distributed or Failed
+#[derive(Serialize, Deserialize, Clone)]
+pub enum FromWorker {
+    Progress(Partial),
+    Done(SolverResult),
+    Swept(Vec<SweepPoint>),
+    Distributed(Distribution),
+    Failed(WorkerError),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum WorkerError {
+    Solver(SolverError),
+    VersionMismatch { expected: u32, received: u32 },
+    // Not an envelope, or one that doesn't match its version
+    Malformed,
+}
+
+impl std::fmt::Display for WorkerError {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            WorkerError::Solver(error) => write!(f, "{}", error),
+            WorkerError::VersionMismatch {
+                expected,
+                received,
+            } => write!(
+                f,
+                "Expected protocol version {} but received {}, try reloading the page",
+                expected, received
+            ),
+            WorkerError::Malformed => write!(
+                f,
+                "Received a message that isn't an envelope, try reloading the page"
+            ),
+        }
     }
 }
 
-impl Worker for MoivreLaplaceSolver {
-    type Message = ();
+impl From<SolverError> for WorkerError {
+    fn from(error: SolverError) -> Self {
+        WorkerError::Solver(error)
+    }
+}
 
-    type Input = SolverRequest;
+impl SolverRequest {
+    pub fn exponent_iterations(&self) -> Iterations {
+        if self.reduce_exponent {
+            Iterations::Reduced(self.precision)
+        } else if self.automatic_iterations {
+            Iterations::UntilStable(self.stable_amount)
+        } else {
+            Iterations::Fixed(self.iterations)
+        }
+    }
 
-    type Output = SolverResult;
+    pub fn square_root(&self) -> SquareRoot {
+        if self.sqrt_to_digits {
+            SquareRoot::Digits(self.sqrt_digits)
+        } else {
+            SquareRoot::Iterations(self.sqrt_iterations)
+        }
+    }
 
-    fn create(
-        _scope: &gloo_worker::WorkerScope<Self>,
-    ) -> Self {
-        Self {}
+    pub fn settings<'a>(
+        &self,
+        progress: &'a dyn Fn(Partial),
+        cancellation: &'a Cancellation,
+    ) -> Settings<'a> {
+        Settings {
+            iterations: self.exponent_iterations(),
+            square_root: self.square_root(),
+            pi_digits: self.precision,
+            digits: self.precision,
+            progress,
+            cancellation,
+        }
     }
 
-    fn update(
-        &mut self,
-        _scope: &gloo_worker::WorkerScope<Self>,
-        _msg: Self::Message,
-    ) {
+    pub fn query(self) -> Query {
+        let (outcomes, to) = match self.interval {
+            Some((from, to)) => (from, Some(to)),
+            None => (self.required, None),
+        };
+        Query {
+            experiments: self.total,
+            outcomes,
+            to,
+            probability: self.odds,
+            edgeworth_order: self.edgeworth_order,
+            stirling_terms: self.stirling_terms,
+        }
     }
+}
 
-    fn received(
-        &mut self,
-        scope: &gloo_worker::WorkerScope<Self>,
-        msg: Self::Input,
-        id: gloo_worker::HandlerId,
-    ) {
-        scope.respond(
-            id,
-            moivre_laplace(
-                msg.total,
-                msg.required,
-                msg.odds,
-                msg.iterations,
-                msg.sqrt_iterations,
-            ),
+// Answers one encoded request of the app, see bridge::register
+pub fn handle(
+    bytes: &[u8],
+    respond: &dyn Fn(Envelope<FromWorker>),
+) {
+    let envelope = match decode::<ToWorker>(bytes) {
+        Ok(envelope) => envelope,
+        Err(error) => {
+            return respond(Envelope::new(
+                error.id,
+                FromWorker::Failed(error.message),
+            ))
+        }
+    };
+    let id = envelope.id;
+    let answer =
+        |message| respond(Envelope::new(id, message));
+
+    // Nothing cancels a job from inside the worker, the app
+    // terminates the whole worker instead
+    let cancellation = Cancellation::new();
+    let progress =
+        |partial| answer(FromWorker::Progress(partial));
+    let result = match envelope.message {
+        ToWorker::Compute(method, msg) => {
+            let settings =
+                msg.settings(&progress, &cancellation);
+            method
+                .solve(msg.query(), settings)
+                .map(FromWorker::Done)
+        }
+        ToWorker::Sweep(request) => {
+            sweep(&request, &progress, &cancellation)
+                .map(FromWorker::Swept)
+        }
+        ToWorker::Distribution(
+            experiments,
+            odds,
+            start,
+            deviations,
+        ) => distribution(
+            experiments,
+            odds,
+            start,
+            deviations,
+            &progress,
+            &cancellation,
         )
+        .map(FromWorker::Distributed),
+        // A job runs synchronously, so a Cancel is only read
+        // once the job it names is over and has answered. All
+        // that is left is to confirm it under the id of that
+        // job. The app doesn't wait for that and stops a
+        // running job by terminating the worker instead
+        ToWorker::Cancel { id: job } => {
+            return respond(Envelope::new(
+                job,
+                FromWorker::Failed(
+                    SolverError::Cancelled.into(),
+                ),
+            ))
+        }
+    };
+
+    answer(result.unwrap_or_else(|error| {
+        FromWorker::Failed(error.into())
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    #[test]
+    fn other_versions_get_a_mismatch_instead_of_a_panic() {
+        // A message shape this version doesn't know about
+        let bytes = encode(&Envelope {
+            version: PROTOCOL_VERSION + 1,
+            id: 7,
+            message: "a request from the future"
+                .to_string(),
+        });
+
+        let error =
+            decode::<ToWorker>(&bytes).err().unwrap();
+        assert_eq!(error.id, 7);
+        assert!(matches!(
+            error.message,
+            WorkerError::VersionMismatch { expected, received }
+                if expected == PROTOCOL_VERSION
+                    && received == PROTOCOL_VERSION + 1
+        ));
+
+        let responses = RefCell::new(Vec::new());
+        handle(&bytes, &|envelope| {
+            responses.borrow_mut().push(envelope)
+        });
+        let responses = responses.into_inner();
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].id, 7);
+        assert!(matches!(
+            responses[0].message,
+            FromWorker::Failed(
+                WorkerError::VersionMismatch { .. }
+            )
+        ));
+    }
+
+    #[test]
+    fn cancel_is_confirmed_under_the_id_of_its_job() {
+        let responses = RefCell::new(Vec::new());
+        handle(
+            &encode(&Envelope::new(
+                4,
+                ToWorker::Cancel { id: 3 },
+            )),
+            &|envelope| {
+                responses.borrow_mut().push(envelope)
+            },
+        );
+        let responses = responses.into_inner();
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].id, 3);
+        assert!(matches!(
+            responses[0].message,
+            FromWorker::Failed(WorkerError::Solver(
+                SolverError::Cancelled
+            ))
+        ));
+    }
+
+    #[test]
+    fn bytes_that_are_no_envelope_are_malformed() {
+        assert!(matches!(
+            decode::<ToWorker>(&[1, 2, 3]),
+            Err(Envelope {
+                message: WorkerError::Malformed,
+                ..
+            })
+        ));
     }
 }